@@ -1,7 +1,9 @@
 use sp_core::{Pair, Public, sr25519};
 use node_template_runtime::{
-	AccountId, AuraConfig, BalancesConfig, GenesisConfig, GrandpaConfig,
-	SudoConfig, SystemConfig, WASM_BINARY, Signature
+	AccountId, AssetFeesConfig, AuraConfig, BalancesConfig, GenesisConfig, GrandpaConfig,
+	IndicesConfig, OracleConfig, RegistrarsConfig, RewardsConfig, SudoConfig, SystemConfig,
+	TreasuryConfig,
+	WASM_BINARY, Signature, AssetId, Balance, FixedPointNumber, FixedU128, UNITS,
 };
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 use sp_finality_grandpa::AuthorityId as GrandpaId;
@@ -62,6 +64,18 @@ pub fn development_config() -> Result<ChainSpec, String> {
 				get_account_id_from_seed::<sr25519::Public>("Alice//stash"),
 				get_account_id_from_seed::<sr25519::Public>("Bob//stash"),
 			],
+			// Identity registrars
+			vec![
+				get_account_id_from_seed::<sr25519::Public>("Alice"),
+			],
+			// Block author reward
+			UNITS,
+			// Assets accepted for fees, at one unit per unit of the native currency
+			vec![
+				(1, FixedU128::saturating_from_integer(1)),
+			],
+			// Endow the pre-funded accounts with the fee assets too
+			true,
 			true,
 		),
 		// Bootnodes
@@ -110,6 +124,18 @@ pub fn local_testnet_config() -> Result<ChainSpec, String> {
 				get_account_id_from_seed::<sr25519::Public>("Eve//stash"),
 				get_account_id_from_seed::<sr25519::Public>("Ferdie//stash"),
 			],
+			// Identity registrars
+			vec![
+				get_account_id_from_seed::<sr25519::Public>("Alice"),
+			],
+			// Block author reward
+			UNITS,
+			// Assets accepted for fees, at one unit per unit of the native currency
+			vec![
+				(1, FixedU128::saturating_from_integer(1)),
+			],
+			// Endow the pre-funded accounts with the fee assets too
+			false,
 			true,
 		),
 		// Bootnodes
@@ -131,6 +157,10 @@ fn testnet_genesis(
 	initial_authorities: Vec<(AuraId, GrandpaId)>,
 	root_key: AccountId,
 	endowed_accounts: Vec<AccountId>,
+	registrars: Vec<AccountId>,
+	block_reward: Balance,
+	fee_assets: Vec<(AssetId, FixedU128)>,
+	endow_fee_assets: bool,
 	_enable_println: bool,
 ) -> GenesisConfig {
 	GenesisConfig {
@@ -149,9 +179,42 @@ fn testnet_genesis(
 		pallet_grandpa: GrandpaConfig {
 			authorities: initial_authorities.iter().map(|x| (x.1.clone(), 1)).collect(),
 		},
+		pallet_indices: IndicesConfig {
+			// No index is reserved at genesis, accounts claim them on-chain.
+			indices: vec![],
+		},
 		pallet_sudo: SudoConfig {
 			// Assign network admin rights.
-			key: root_key,
+			key: root_key.clone(),
+		},
+		pallet_treasury: TreasuryConfig::default(),
+		pallet_rewards: RewardsConfig {
+			// Minted to the author of every block.
+			block_reward,
+		},
+		pallet_registrars: RegistrarsConfig {
+			// Registrars provide judgements free of charge on test networks.
+			registrars: registrars.into_iter().map(|k| (k, 0)).collect(),
+		},
+		pallet_asset_fees: AssetFeesConfig {
+			// Fee assets are sufficient, so that accounts holding no native currency can pay
+			// fees with them.
+			assets: fee_assets.iter().map(|(id, _)| (*id, root_key.clone(), true, 1)).collect(),
+			// Only development chains mint fee assets out of thin air, to try asset fees out.
+			balances: if endow_fee_assets {
+				fee_assets.iter()
+					.flat_map(|(id, _)| {
+						endowed_accounts.iter().map(move |k| (*id, k.clone(), 1 << 60))
+					})
+					.collect()
+			} else {
+				vec![]
+			},
+			conversion_rates: fee_assets,
+		},
+		pallet_oracle: OracleConfig {
+			// The root account feeds asset prices until proper feeders are added.
+			feeders: vec![root_key],
 		},
 	}
 }
@@ -1,5 +1,9 @@
+use std::net::{Ipv4Addr, SocketAddr};
 use structopt::StructOpt;
-use sc_cli::RunCmd;
+use sc_cli::{RpcMethods, RunCmd};
+
+use crate::cmd::{DbStatsCmd, ForkOffCmd, IndexEventsCmd, InspectCmd, TxCmd};
+use crate::health::{HealthConfig, HealthThresholds};
 
 #[derive(Debug, StructOpt)]
 pub struct Cli {
@@ -8,6 +12,210 @@ pub struct Cli {
 
 	#[structopt(flatten)]
 	pub run: RunCmd,
+
+	#[structopt(flatten)]
+	pub pool: PoolParams,
+
+	#[structopt(flatten)]
+	pub health: HealthParams,
+
+	/// Set consistent defaults for what the node is run for.
+	///
+	/// `archive` keeps the state of every block, with larger caches, for indexers and explorers.
+	/// `full` keeps the state of the last 256 blocks with the default caches. `light-server` keeps
+	/// the state of the last 4096 blocks, which light clients request proofs of, with a larger
+	/// state cache and more WebSocket connections. Only the safe RPC methods are exposed by
+	/// `archive` and `light-server`: they turn the default `--rpc-methods auto` into `safe`, which
+	/// also disables the unsafe methods otherwise exposed on localhost, such as `author_insertKey`.
+	/// Pass `--rpc-methods unsafe` to insert keys through RPC with these profiles.
+	///
+	/// `--pruning`, `--db-cache`, `--rpc-methods` and `--ws-max-connections` override the profile,
+	/// as does a `--state-cache-size` other than the default. Authorities, run with `--validator`,
+	/// `--dev` or a keyring flag such as `--alice`, keep the state of every block whatever the
+	/// profile, as Substrate only lets them prune it with `--unsafe-pruning`.
+	#[structopt(
+		long,
+		value_name = "PROFILE",
+		possible_values = &["archive", "full", "light-server"],
+	)]
+	pub profile: Option<Profile>,
+
+	/// The format of the logs written to stderr.
+	///
+	/// `json` writes a JSON object per line with the timestamp, level, target and message of each
+	/// record, and the block number, block hash and peer id it mentions, found in the message on a
+	/// best effort basis. Levels are set per target with `--log` and `RUST_LOG` in both formats.
+	/// `--tracing-targets`, `--tracing-receiver` and `--enable-log-reloading` are only supported
+	/// with `text`.
+	#[structopt(
+		long,
+		global = true,
+		value_name = "FORMAT",
+		possible_values = &["text", "json"],
+		default_value = "text",
+	)]
+	pub log_format: LogFormat,
+}
+
+/// The format of the logs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogFormat {
+	/// Human-readable lines, as formatted by Substrate.
+	Text,
+	/// A JSON object per line.
+	Json,
+}
+
+impl std::str::FromStr for LogFormat {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"text" => Ok(Self::Text),
+			"json" => Ok(Self::Json),
+			_ => Err(format!("Unknown log format: {}", s)),
+		}
+	}
+}
+
+/// A set of defaults for what the node is run for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Profile {
+	/// Keep the state of every block.
+	Archive,
+	/// Keep the state of recent blocks.
+	Full,
+	/// Keep the state of the blocks light clients request proofs of.
+	LightServer,
+}
+
+impl std::str::FromStr for Profile {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"archive" => Ok(Self::Archive),
+			"full" => Ok(Self::Full),
+			"light-server" => Ok(Self::LightServer),
+			_ => Err(format!("Unknown profile: {}", s)),
+		}
+	}
+}
+
+/// The default of `--state-cache-size`, in bytes.
+const DEFAULT_STATE_CACHE_SIZE: usize = 67108864;
+
+impl Profile {
+	/// Apply the defaults of this profile to the options of `RunCmd` left to their defaults.
+	///
+	/// The pruning of authorities is left to Substrate, which keeps the state of every block.
+	pub fn apply(&self, run: &mut RunCmd) {
+		let (pruning, db_cache_mib, state_cache_size, ws_max_connections) = match self {
+			Self::Archive => ("archive", 1024, 256 * 1024 * 1024, 100),
+			Self::Full => ("256", 128, DEFAULT_STATE_CACHE_SIZE, 100),
+			Self::LightServer => ("4096", 512, 256 * 1024 * 1024, 1000),
+		};
+
+		let is_authority = run.validator || run.shared_params.dev || run.get_keyring().is_some();
+		let import_params = &mut run.import_params;
+		if !is_authority {
+			import_params.pruning_params.pruning.get_or_insert_with(|| pruning.into());
+		}
+		import_params.database_params.database_cache_size.get_or_insert(db_cache_mib);
+		if import_params.state_cache_size == DEFAULT_STATE_CACHE_SIZE {
+			import_params.state_cache_size = state_cache_size;
+		}
+		run.ws_max_connections.get_or_insert(ws_max_connections);
+		if *self != Self::Full && run.rpc_methods == RpcMethods::Auto {
+			run.rpc_methods = RpcMethods::Safe;
+		}
+	}
+}
+
+/// Transaction pool limits complementing `--pool-limit` and `--pool-kbytes`.
+#[derive(Debug, Clone, StructOpt)]
+pub struct PoolParams {
+	/// Maximum number of transactions waiting for a missing nonce in the transaction pool.
+	///
+	/// Defaults to a tenth of `--pool-limit`.
+	#[structopt(long = "pool-future-limit", value_name = "COUNT")]
+	pub pool_future_limit: Option<usize>,
+
+	/// Maximum number of kilobytes of all transactions waiting for a missing nonce.
+	///
+	/// Defaults to a tenth of `--pool-kbytes`.
+	#[structopt(long = "pool-future-kbytes", value_name = "COUNT")]
+	pub pool_future_kbytes: Option<usize>,
+
+	/// Reject transactions waiting for a missing nonce instead of keeping them in the pool.
+	#[structopt(long = "pool-reject-future")]
+	pub pool_reject_future: bool,
+}
+
+impl PoolParams {
+	/// Apply these limits on top of the transaction pool options built by `RunCmd`.
+	pub fn apply(&self, options: &mut sc_service::config::TransactionPoolOptions) {
+		if let Some(count) = self.pool_future_limit {
+			options.future.count = count;
+		}
+		if let Some(kbytes) = self.pool_future_kbytes {
+			options.future.total_bytes = kbytes * 1024;
+		}
+		if self.pool_reject_future {
+			options.reject_future_transactions = true;
+		}
+	}
+}
+
+/// The HTTP endpoint liveness and readiness probes query.
+#[derive(Debug, Clone, StructOpt)]
+pub struct HealthParams {
+	/// Serve `/health` and `/ready` over HTTP on this TCP port.
+	///
+	/// Both answer 200 when the node is healthy, or ready, and 503 otherwise, with a JSON report
+	/// of its peers, syncing state and best and finalized heights.
+	#[structopt(long = "health-port", value_name = "PORT")]
+	pub health_port: Option<u16>,
+
+	/// Listen to all interfaces for health requests.
+	///
+	/// Default is local.
+	#[structopt(long = "health-external")]
+	pub health_external: bool,
+
+	/// The node is unhealthy, and not ready, with fewer peers.
+	///
+	/// Default is 0, so that a node without peers, such as a `--dev` one, is healthy. Set it to
+	/// at least 1 for nodes of a network, which are of no use to it when isolated.
+	#[structopt(long = "health-min-peers", value_name = "COUNT", default_value = "0")]
+	pub health_min_peers: usize,
+
+	/// The node is unhealthy when its best block is more blocks ahead of the last finalized one.
+	#[structopt(long = "health-max-finality-lag", value_name = "BLOCKS", default_value = "64")]
+	pub health_max_finality_lag: u32,
+
+	/// The node is unhealthy when its local authority missed more consecutive slots.
+	#[structopt(long = "health-max-missed-slots", value_name = "SLOTS", default_value = "3")]
+	pub health_max_missed_slots: u64,
+}
+
+impl HealthParams {
+	/// The configuration of the endpoint, `None` if `--health-port` isn't given.
+	pub fn config(&self) -> Option<HealthConfig> {
+		let interface = if self.health_external {
+			Ipv4Addr::UNSPECIFIED
+		} else {
+			Ipv4Addr::LOCALHOST
+		};
+		self.health_port.map(|port| HealthConfig {
+			address: SocketAddr::new(interface.into(), port),
+			thresholds: HealthThresholds {
+				min_peers: self.health_min_peers,
+				max_finality_lag: self.health_max_finality_lag,
+				max_missed_slots: self.health_max_missed_slots,
+			},
+		})
+	}
 }
 
 #[derive(Debug, StructOpt)]
@@ -35,6 +243,21 @@ pub enum Subcommand {
 	/// Revert the chain to a previous state.
 	Revert(sc_cli::RevertCmd),
 
+	/// Decode extrinsics, blocks and storage keys.
+	Inspect(InspectCmd),
+
+	/// Sign a transaction and submit it to a node.
+	Tx(TxCmd),
+
+	/// Report the size of the database by column and of the state by pallet.
+	DbStats(DbStatsCmd),
+
+	/// Write a chain spec forking off the state of another chain with development keys.
+	ForkOff(ForkOffCmd),
+
+	/// Index the runtime events of a range of blocks into a JSON Lines file.
+	IndexEvents(IndexEventsCmd),
+
 	/// The custom benchmark subcommmand benchmarking runtime pallets.
 	#[structopt(name = "benchmark", about = "Benchmark runtime pallets.")]
 	Benchmark(frame_benchmarking_cli::BenchmarkCmd),
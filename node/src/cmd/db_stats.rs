@@ -0,0 +1,200 @@
+//! The `db-stats` subcommand, reporting the size of the database by column and the size of the
+//! state by pallet and storage item.
+
+use std::collections::BTreeMap;
+
+use kvdb::KeyValueDB;
+use node_template_runtime::opaque::Block;
+use sc_cli::{CliConfiguration, DatabaseParams, PruningParams, SharedParams};
+use sc_client_api::StorageProvider;
+use sc_service::{config::DatabaseConfig, Configuration, PartialComponents};
+use sp_blockchain::HeaderBackend;
+use sp_runtime::generic::BlockId;
+use structopt::StructOpt;
+
+use super::storage::StorageItems;
+use crate::service;
+
+/// The columns of the database of a full node, in the order `sc-client-db` opens them.
+const COLUMNS: [&str; 12] = [
+	"meta",
+	"state",
+	"state_meta",
+	"key_lookup",
+	"header",
+	"body",
+	"justifications",
+	"changes_trie",
+	"aux",
+	"offchain",
+	"cache",
+	"transaction",
+];
+
+/// Report the size of the database by column, and the size of the state of the best block by
+/// pallet and storage item.
+///
+/// The node must not be running, as the database is opened directly. Light client databases are
+/// not supported.
+#[derive(Debug, StructOpt)]
+pub struct DbStatsCmd {
+	/// Only report the total size of each pallet, not of each storage item.
+	#[structopt(long)]
+	pub pallets_only: bool,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub pruning_params: PruningParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub database_params: DatabaseParams,
+}
+
+/// The number and size of the entries of a column or of a storage item.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Usage {
+	/// The number of entries.
+	pub entries: u64,
+	/// The bytes of the keys.
+	pub key_bytes: u64,
+	/// The bytes of the values.
+	pub value_bytes: u64,
+}
+
+impl Usage {
+	/// Count an entry.
+	pub fn add(&mut self, key: &[u8], value: &[u8]) {
+		self.entries += 1;
+		self.key_bytes += key.len() as u64;
+		self.value_bytes += value.len() as u64;
+	}
+
+	/// Count the entries of `other`.
+	pub fn add_all(&mut self, other: &Usage) {
+		self.entries += other.entries;
+		self.key_bytes += other.key_bytes;
+		self.value_bytes += other.value_bytes;
+	}
+
+	/// The bytes of the keys and the values.
+	pub fn total_bytes(&self) -> u64 {
+		self.key_bytes + self.value_bytes
+	}
+}
+
+impl DbStatsCmd {
+	/// Run the command on the database of `config`.
+	pub fn run(&self, config: Configuration) -> sc_cli::Result<()> {
+		match &config.database {
+			DatabaseConfig::RocksDb { path, .. } => {
+				// Opened before the client, which would hold the lock of the database.
+				let db = kvdb_rocksdb::Database::open(
+					&kvdb_rocksdb::DatabaseConfig::with_columns(COLUMNS.len() as u32),
+					&path.to_string_lossy(),
+				)?;
+				println!("Database columns:");
+				print_header();
+				let mut total = Usage::default();
+				for (column, name) in COLUMNS.iter().enumerate() {
+					let mut usage = Usage::default();
+					for (key, value) in db.iter(column as u32) {
+						usage.add(&key, &value);
+					}
+					print_usage(name, &usage);
+					total.add_all(&usage);
+				}
+				print_usage("total", &total);
+				println!();
+			},
+			database => {
+				println!("Column sizes are only reported for RocksDB, not {:?}", database);
+				println!();
+			},
+		}
+
+		let PartialComponents { client, .. } = service::new_partial(&config)?;
+		let best = client.info().best_hash;
+		let (pallets, items) = state_usage(&*client, &BlockId::Hash(best))?;
+
+		println!("State of the best block {:?} by pallet:", best);
+		print_header();
+		for (pallet, usage) in &pallets {
+			print_usage(pallet, usage);
+		}
+		if !self.pallets_only {
+			println!();
+			println!("State of the best block by storage item:");
+			print_header();
+			for ((pallet, item), usage) in &items {
+				print_usage(&format!("{}::{}", pallet, item), usage);
+			}
+		}
+		Ok(())
+	}
+}
+
+impl CliConfiguration for DbStatsCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn pruning_params(&self) -> Option<&PruningParams> {
+		Some(&self.pruning_params)
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
+
+/// The usage of the state at `id`, by pallet and by pallet and storage item.
+///
+/// Well known keys like `:code` are counted under the `:well_known` pallet, and keys of no pallet
+/// of the runtime under `unknown`.
+pub fn state_usage<C, BA>(
+	client: &C,
+	id: &BlockId<Block>,
+) -> sc_cli::Result<(BTreeMap<String, Usage>, BTreeMap<(String, String), Usage>)> where
+	C: StorageProvider<Block, BA>,
+	BA: sc_client_api::Backend<Block>,
+{
+	let storage_items = StorageItems::of_runtime();
+	let mut pallets = BTreeMap::<String, Usage>::new();
+	let mut items = BTreeMap::<(String, String), Usage>::new();
+
+	for key in client.storage_keys_iter(id, None, None)? {
+		let value = client.storage(id, &key)?.map(|value| value.0).unwrap_or_default();
+		let (pallet, item) = match storage_items.item(&key.0) {
+			Some(item) => (item.pallet.clone(), item.item.clone()),
+			None if key.0.starts_with(b":") => {
+				(":well_known".into(), String::from_utf8_lossy(&key.0).into_owned())
+			},
+			None => match storage_items.pallet(&key.0) {
+				Some(pallet) => (pallet.into(), "unknown".into()),
+				None => ("unknown".into(), "unknown".into()),
+			},
+		};
+		pallets.entry(pallet.clone()).or_default().add(&key.0, &value);
+		items.entry((pallet, item)).or_default().add(&key.0, &value);
+	}
+	Ok((pallets, items))
+}
+
+fn print_header() {
+	println!(
+		"{:<48} {:>12} {:>14} {:>14} {:>14}",
+		"", "entries", "key bytes", "value bytes", "total bytes",
+	);
+}
+
+fn print_usage(name: &str, usage: &Usage) {
+	println!(
+		"{:<48} {:>12} {:>14} {:>14} {:>14}",
+		name, usage.entries, usage.key_bytes, usage.value_bytes, usage.total_bytes(),
+	);
+}
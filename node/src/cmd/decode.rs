@@ -0,0 +1,153 @@
+//! Rendering of the values of the native runtime as JSON, and access to its metadata.
+//!
+//! Values are decoded as the types of the native runtime, not from the type names found in the
+//! metadata, so that a change to a type of the runtime is a change to how the node decodes it.
+
+use codec::{Decode, Encode};
+use frame_metadata::{DecodeDifferent, ModuleMetadata, RuntimeMetadata, RuntimeMetadataPrefixed};
+use frame_support::{traits::BalanceStatus, weights::DispatchInfo};
+use node_template_runtime::{AccountId, FixedPointNumber, FixedU128, Hash, Runtime};
+use serde_json::{json, Value};
+use sp_core::{bytes::to_hex, crypto::Ss58Codec};
+use sp_runtime::DispatchError;
+
+/// The modules of the native runtime, as described by its metadata.
+pub fn runtime_modules() -> Vec<ModuleMetadata> {
+	// The native metadata refers to static data, decoding it from its encoding owns it.
+	let encoded = Runtime::metadata().encode();
+	let metadata = RuntimeMetadataPrefixed::decode(&mut &encoded[..])
+		.expect("the metadata of the native runtime decodes; qed");
+	match metadata.1 {
+		RuntimeMetadata::V13(metadata) => decoded(metadata.modules),
+		_ => unreachable!("the native runtime has V13 metadata; qed"),
+	}
+}
+
+/// The value of a part of metadata decoded from its encoding.
+pub fn decoded<B, O>(value: DecodeDifferent<B, O>) -> O {
+	match value {
+		DecodeDifferent::Decoded(value) => value,
+		DecodeDifferent::Encode(_) => unreachable!("decoded metadata has nothing to encode; qed"),
+	}
+}
+
+/// A value of the native runtime which can be written as JSON.
+///
+/// Balances and other 128 bit integers are written as strings, as most JSON parsers can't
+/// represent them exactly.
+pub trait ToJson {
+	/// The value as JSON.
+	fn to_json(&self) -> Value;
+}
+
+/// Decode a `T` from `input`, as JSON.
+pub fn decode_json<T: Decode + ToJson>(input: &mut &[u8]) -> Result<Value, codec::Error> {
+	Ok(T::decode(input)?.to_json())
+}
+
+macro_rules! impl_to_json_for_numbers {
+	($($ty:ty),*) => {
+		$(
+			impl ToJson for $ty {
+				fn to_json(&self) -> Value {
+					(*self).into()
+				}
+			}
+		)*
+	};
+}
+
+impl_to_json_for_numbers!(bool, u8, u16, u32, u64);
+
+impl ToJson for u128 {
+	fn to_json(&self) -> Value {
+		self.to_string().into()
+	}
+}
+
+impl ToJson for String {
+	fn to_json(&self) -> Value {
+		self.as_str().into()
+	}
+}
+
+impl ToJson for () {
+	fn to_json(&self) -> Value {
+		Value::Null
+	}
+}
+
+impl ToJson for AccountId {
+	fn to_json(&self) -> Value {
+		self.to_ss58check().into()
+	}
+}
+
+impl ToJson for Hash {
+	fn to_json(&self) -> Value {
+		to_hex(self.as_bytes(), false).into()
+	}
+}
+
+impl ToJson for FixedU128 {
+	fn to_json(&self) -> Value {
+		let inner = self.into_inner();
+		let accuracy = FixedU128::accuracy();
+		format!("{}.{:018}", inner / accuracy, inner % accuracy).into()
+	}
+}
+
+impl ToJson for DispatchInfo {
+	fn to_json(&self) -> Value {
+		json!({
+			"weight": self.weight,
+			"class": format!("{:?}", self.class),
+			"paysFee": format!("{:?}", self.pays_fee),
+		})
+	}
+}
+
+impl ToJson for DispatchError {
+	fn to_json(&self) -> Value {
+		format!("{:?}", self).into()
+	}
+}
+
+impl ToJson for BalanceStatus {
+	fn to_json(&self) -> Value {
+		format!("{:?}", self).into()
+	}
+}
+
+impl ToJson for sp_finality_grandpa::AuthorityId {
+	fn to_json(&self) -> Value {
+		to_hex(self.as_ref(), false).into()
+	}
+}
+
+impl<T: ToJson, E: ToJson> ToJson for Result<T, E> {
+	fn to_json(&self) -> Value {
+		match self {
+			Ok(value) => json!({ "ok": value.to_json() }),
+			Err(error) => json!({ "err": error.to_json() }),
+		}
+	}
+}
+
+impl<T: ToJson> ToJson for Option<T> {
+	fn to_json(&self) -> Value {
+		self.as_ref().map_or(Value::Null, T::to_json)
+	}
+}
+
+impl<T: ToJson> ToJson for Vec<T> {
+	fn to_json(&self) -> Value {
+		self.iter().map(T::to_json).collect()
+	}
+}
+
+impl<A: ToJson, B: ToJson> ToJson for (A, B) {
+	fn to_json(&self) -> Value {
+		vec![self.0.to_json(), self.1.to_json()].into()
+	}
+}
@@ -0,0 +1,185 @@
+//! The `fork-off` subcommand, turning the state of a chain into a chain spec runnable with the
+//! development keys.
+
+use std::{fs::File, io::Write, path::{Path, PathBuf}};
+
+use codec::{Decode, Encode};
+use node_template_runtime::{AccountId, Balance, BlockNumber};
+use pallet_escrow::{EscrowDetails, EscrowId};
+use sc_cli::{CliConfiguration, SharedParams};
+use sc_service::ChainSpec;
+use sp_core::{storage::Storage, twox_128, twox_64, Bytes};
+use sp_finality_grandpa::GRANDPA_AUTHORITIES_KEY;
+use structopt::StructOpt;
+
+use crate::chain_spec;
+
+/// Write a chain spec starting from the state of another chain, with the authorities and the
+/// sudo key of the chain spec selected by `--chain`.
+///
+/// The accounts of the development keys only hold what they held in the forked state. Sudo can
+/// endow them with `balances::set_balance`.
+///
+/// The fork starts again from block 0. The expiries of escrows and the blocks of the last free
+/// calls are rebased on the forked block, so that they are as many blocks away on the fork.
+/// What is derived from the block number itself is not: the block reward is the one of the
+/// first blocks again, before any halving, commit-reveal rounds start over with the commitments
+/// and reveals of the forked round kept, and oracle values look as old as their block number.
+#[derive(Debug, StructOpt)]
+pub struct ForkOffCmd {
+	/// The state to fork off: a raw chain spec, as written by `export-state`, or the response of
+	/// the `state_getPairs` RPC of a node, or its result.
+	///
+	/// Child tries are only kept from chain specs.
+	#[structopt(parse(from_os_str), value_name = "FILE")]
+	pub input: PathBuf,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+}
+
+impl ForkOffCmd {
+	/// Run the command, taking the keys and the rest of the chain spec from `spec`.
+	pub fn run(&self, mut spec: Box<dyn ChainSpec>) -> sc_cli::Result<()> {
+		let dev = spec.as_storage_builder().build_storage()?;
+		let forked = load_state(&self.input)?;
+		spec.set_storage(fork_off(forked, &dev));
+
+		let json = sc_service::chain_ops::build_spec(&*spec, true)?;
+		if std::io::stdout().write_all(json.as_bytes()).is_err() {
+			let _ = std::io::stderr().write_all(b"Error writing to stdout\n");
+		}
+		Ok(())
+	}
+}
+
+impl CliConfiguration for ForkOffCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+}
+
+/// Load the state in `path`, a chain spec or the response of `state_getPairs`.
+fn load_state(path: &Path) -> sc_cli::Result<Storage> {
+	let json: serde_json::Value = serde_json::from_reader(File::open(path)?)
+		.map_err(|e| format!("Invalid state file {}: {}", path.display(), e))?;
+	if json.get("genesis").is_some() {
+		let spec = chain_spec::ChainSpec::from_json_file(path.to_path_buf())?;
+		return spec.as_storage_builder().build_storage().map_err(Into::into)
+	}
+
+	let pairs = json.get("result").cloned().unwrap_or(json);
+	let pairs: Vec<(Bytes, Bytes)> = serde_json::from_value(pairs)
+		.map_err(|e| format!("Invalid state file {}: {}", path.display(), e))?;
+	Ok(Storage {
+		top: pairs.into_iter().map(|(key, value)| (key.0, value.0)).collect(),
+		children_default: Default::default(),
+	})
+}
+
+/// The prefix of the keys of the storage `item` of `pallet`.
+fn item(pallet: &str, item: &str) -> Vec<u8> {
+	[twox_128(pallet.as_bytes()), twox_128(item.as_bytes())].concat()
+}
+
+/// The prefixes of the keys `fork_off` takes from the chain spec rather than the forked state.
+fn replaced_prefixes() -> Vec<Vec<u8>> {
+	vec![
+		// The authorities, along with their set ids, slots and scheduled changes.
+		twox_128(b"Aura").to_vec(),
+		twox_128(b"Grandpa").to_vec(),
+		GRANDPA_AUTHORITIES_KEY.to_vec(),
+		item("Sudo", "Key"),
+		// What `frame_system` keeps about the last block, which the genesis block doesn't follow.
+		item("System", "Number"),
+		item("System", "ParentHash"),
+		item("System", "BlockHash"),
+		item("System", "ExtrinsicData"),
+		item("System", "ExtrinsicCount"),
+		item("System", "BlockWeight"),
+		item("System", "AllExtrinsicsLen"),
+		item("System", "Digest"),
+		item("System", "Events"),
+		item("System", "EventCount"),
+		item("System", "EventTopics"),
+	]
+}
+
+/// The keys of a storage map hashed with `Twox64Concat`, under `prefix`, along with their
+/// values.
+fn map_entries<K: Decode>(state: &Storage, prefix: &[u8]) -> Vec<(Vec<u8>, K, Vec<u8>)> {
+	state.top.iter()
+		.filter_map(|(key, value)| {
+			let mut encoded = key.strip_prefix(prefix)?.get(8..)?;
+			Some((key.clone(), K::decode(&mut encoded).ok()?, value.clone()))
+		})
+		.collect()
+}
+
+/// Rebase the block numbers kept in `state` on its block, so that the block following it is
+/// block 1 of the fork.
+fn rebase_block_numbers(state: &mut Storage) {
+	let number = match state.top.get(&item("System", "Number"))
+		.and_then(|value| BlockNumber::decode(&mut &value[..]).ok())
+	{
+		Some(number) => number,
+		None => return,
+	};
+	// Escrows left in the forked state expire after its block, in block 1 of the fork at least.
+	let expiry = |block: BlockNumber| block.saturating_sub(number).max(1);
+
+	let expiring = item("Escrow", "Expiring");
+	let entries = map_entries::<BlockNumber>(state, &expiring);
+	for (key, _, _) in &entries {
+		state.top.remove(key);
+	}
+	for (_, block, value) in entries {
+		let block = expiry(block).encode();
+		let key = [&expiring[..], &twox_64(&block), &block].concat();
+		let decode = |value: &[u8]| Vec::<EscrowId>::decode(&mut &value[..]).unwrap_or_default();
+		let mut escrows = state.top.get(&key).map(|existing| decode(existing)).unwrap_or_default();
+		escrows.extend(decode(&value));
+		state.top.insert(key, escrows.encode());
+	}
+
+	type Escrow = EscrowDetails<AccountId, Balance, BlockNumber>;
+	let escrows = item("Escrow", "Escrows");
+	for value in state.top.iter_mut()
+		.filter(|(key, _)| key.starts_with(&escrows))
+		.map(|(_, value)| value)
+	{
+		if let Ok(mut escrow) = Escrow::decode(&mut &value[..]) {
+			escrow.expiry = expiry(escrow.expiry);
+			*value = escrow.encode();
+		}
+	}
+
+	// Free calls made long before the forked block are taken as made in block 0 of the fork,
+	// which can only make their cooldown longer.
+	let last_free_calls = item("TemplateModule", "LastFreeCall");
+	for value in state.top.iter_mut()
+		.filter(|(key, _)| key.starts_with(&last_free_calls))
+		.map(|(_, value)| value)
+	{
+		if let Ok(block) = BlockNumber::decode(&mut &value[..]) {
+			*value = block.saturating_sub(number).encode();
+		}
+	}
+}
+
+/// The state `forked` with the authorities, the sudo key and the block of `dev`.
+///
+/// Everything else is kept from `forked`, the runtime code included, with its block numbers
+/// rebased on the forked block.
+pub fn fork_off(mut forked: Storage, dev: &Storage) -> Storage {
+	rebase_block_numbers(&mut forked);
+
+	let prefixes = replaced_prefixes();
+	let replaced = |key: &Vec<u8>| prefixes.iter().any(|prefix| key.starts_with(prefix));
+
+	forked.top.retain(|key, _| !replaced(key));
+	let kept = dev.top.iter().filter(|(key, _)| replaced(key));
+	forked.top.extend(kept.map(|(key, value)| (key.clone(), value.clone())));
+	forked
+}
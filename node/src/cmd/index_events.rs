@@ -0,0 +1,366 @@
+//! The `index-events` subcommand, writing the runtime events of a range of blocks as JSON Lines.
+
+use std::{collections::HashMap, fs::File, io::{BufWriter, Write}, path::PathBuf, sync::Arc};
+
+use codec::{Decode, Encode};
+use frame_system::{EventRecord, Phase};
+use node_template_runtime::{opaque::Block, Event, Hash};
+use sc_cli::{CliConfiguration, DatabaseParams, PruningParams, SharedParams};
+use sc_client_api::{backend::Backend, StorageProvider};
+use serde::Serialize;
+use serde_json::{json, Value};
+use sp_blockchain::HeaderBackend;
+use sp_core::{bytes::to_hex, storage::StorageKey, twox_128};
+use sp_runtime::generic::BlockId;
+use structopt::StructOpt;
+
+use super::decode::{decoded, runtime_modules, ToJson};
+
+/// Write the runtime events of a range of blocks to a file, one JSON object per line.
+///
+/// Each line holds the block and the extrinsic that emitted the event, the names of its pallet
+/// and variant, and its fields along with their type names.
+#[derive(Debug, StructOpt)]
+pub struct IndexEventsCmd {
+	/// Number of the first block to index.
+	#[structopt(long, value_name = "BLOCK")]
+	pub from: u32,
+
+	/// Number of the last block to index, included.
+	#[structopt(long, value_name = "BLOCK")]
+	pub to: u32,
+
+	/// File to write the events to.
+	#[structopt(long, parse(from_os_str), value_name = "FILE")]
+	pub out: PathBuf,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub pruning_params: PruningParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub database_params: DatabaseParams,
+}
+
+impl IndexEventsCmd {
+	/// Run the command against the blocks of `client`.
+	pub async fn run<C, BA>(&self, client: Arc<C>) -> sc_cli::Result<()> where
+		C: HeaderBackend<Block> + StorageProvider<Block, BA>,
+		BA: Backend<Block>,
+	{
+		if self.from > self.to {
+			return Err("`--from` must not be after `--to`".into())
+		}
+
+		let descriptions = EventDescriptions::of_runtime();
+		let key = StorageKey([twox_128(b"System"), twox_128(b"Events")].concat());
+		let mut out = BufWriter::new(File::create(&self.out)?);
+		let mut count = 0;
+
+		for number in self.from..=self.to {
+			let hash = client.hash(number)?
+				.ok_or_else(|| format!("Block #{} is not in the database", number))?;
+			let records = match client.storage(&BlockId::Hash(hash), &key)? {
+				Some(data) => Vec::<EventRecord<Event, Hash>>::decode(&mut &data.0[..])
+					.map_err(|e| format!("Events of block #{} don't decode: {}", number, e))?,
+				None => continue,
+			};
+
+			for record in &records {
+				serde_json::to_writer(&mut out, &descriptions.index(number, hash, record))
+					.map_err(|e| e.to_string())?;
+				out.write_all(b"\n")?;
+			}
+			count += records.len();
+		}
+		out.flush()?;
+
+		log::info!(
+			"Indexed {} events of blocks #{} to #{} into {}",
+			count, self.from, self.to, self.out.display(),
+		);
+		Ok(())
+	}
+}
+
+impl CliConfiguration for IndexEventsCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn pruning_params(&self) -> Option<&PruningParams> {
+		Some(&self.pruning_params)
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
+
+/// An event variant of the runtime, as described by its metadata.
+#[derive(Debug)]
+pub struct EventDescription {
+	/// The name of the pallet emitting the event.
+	pub pallet: String,
+	/// The name of the variant.
+	pub variant: String,
+	/// The type names of the fields.
+	pub fields: Vec<String>,
+}
+
+/// The fields of a variant, as JSON.
+macro_rules! fields {
+	($($field:expr),* $(,)?) => {
+		vec![$($field.to_json()),*]
+	};
+}
+
+/// The fields of an event of the native runtime, or `None` if the node doesn't know the
+/// variant.
+// Events declared with `decl_event!` have no hidden variant: the catch-all arms only match
+// variants added to the pallets of the others.
+#[allow(unreachable_patterns)]
+pub fn event_fields(event: &Event) -> Option<Vec<Value>> {
+	use frame_system::Event as System;
+	use pallet_asset_fees::Event as AssetFees;
+	use pallet_assets::Event as Assets;
+	use pallet_balances::Event as Balances;
+	use pallet_commit_reveal::Event as CommitReveal;
+	use pallet_escrow::Event as Escrow;
+	use pallet_grandpa::Event as Grandpa;
+	use pallet_identity::Event as Identity;
+	use pallet_indices::Event as Indices;
+	use pallet_nft::Event as Nft;
+	use pallet_oracle::Event as Oracle;
+	use pallet_rewards::Event as Rewards;
+	use pallet_sudo::Event as Sudo;
+	use pallet_template::Event as Template;
+	use pallet_treasury::Event as Treasury;
+
+	Some(match event {
+		Event::frame_system(event) => match event {
+			System::ExtrinsicSuccess(info) => fields![info],
+			System::ExtrinsicFailed(error, info) => fields![error, info],
+			System::CodeUpdated => fields![],
+			System::NewAccount(who) | System::KilledAccount(who) => fields![who],
+			_ => return None,
+		},
+		Event::pallet_grandpa(event) => match event {
+			Grandpa::NewAuthorities(authorities) => fields![authorities],
+			Grandpa::Paused | Grandpa::Resumed => fields![],
+			_ => return None,
+		},
+		Event::pallet_indices(event) => match event {
+			Indices::IndexAssigned(who, index) => fields![who, index],
+			Indices::IndexFreed(index) => fields![index],
+			Indices::IndexFrozen(index, who) => fields![index, who],
+			_ => return None,
+		},
+		Event::pallet_balances(event) => match event {
+			Balances::Endowed(who, amount)
+				| Balances::DustLost(who, amount)
+				| Balances::Deposit(who, amount)
+				| Balances::Reserved(who, amount)
+				| Balances::Unreserved(who, amount) => fields![who, amount],
+			Balances::Transfer(from, to, amount) => fields![from, to, amount],
+			Balances::BalanceSet(who, free, reserved) => fields![who, free, reserved],
+			Balances::ReserveRepatriated(from, to, amount, status) => {
+				fields![from, to, amount, status]
+			},
+			_ => return None,
+		},
+		Event::pallet_sudo(event) => match event {
+			Sudo::Sudid(result) | Sudo::SudoAsDone(result) => fields![result],
+			Sudo::KeyChanged(who) => fields![who],
+			_ => return None,
+		},
+		Event::pallet_treasury(event) => match event {
+			Treasury::Proposed(proposal) => fields![proposal],
+			Treasury::Awarded(proposal, amount, who) => fields![proposal, amount, who],
+			Treasury::Rejected(proposal, amount) => fields![proposal, amount],
+			Treasury::Spending(amount)
+				| Treasury::Burnt(amount)
+				| Treasury::Rollover(amount)
+				| Treasury::Deposit(amount) => fields![amount],
+			_ => return None,
+		},
+		Event::pallet_identity(event) => match event {
+			Identity::IdentitySet(who) => fields![who],
+			Identity::IdentityCleared(who, deposit) | Identity::IdentityKilled(who, deposit) => {
+				fields![who, deposit]
+			},
+			Identity::JudgementRequested(who, registrar)
+				| Identity::JudgementUnrequested(who, registrar)
+				| Identity::JudgementGiven(who, registrar) => fields![who, registrar],
+			Identity::RegistrarAdded(registrar) => fields![registrar],
+			Identity::SubIdentityAdded(sub, main, deposit)
+				| Identity::SubIdentityRemoved(sub, main, deposit)
+				| Identity::SubIdentityRevoked(sub, main, deposit) => fields![sub, main, deposit],
+			_ => return None,
+		},
+		Event::pallet_rewards(event) => match event {
+			Rewards::RewardPaid(who, amount) => fields![who, amount],
+			Rewards::BlockRewardSet(amount) => fields![amount],
+			_ => return None,
+		},
+		Event::pallet_asset_fees(event) => match event {
+			AssetFees::ConversionRateSet(asset, rate) => fields![asset, rate],
+			AssetFees::FeeAssetSet(who, asset) => fields![who, asset],
+			AssetFees::AssetFeePaid(who, asset, amount) => fields![who, asset, amount],
+			_ => return None,
+		},
+		Event::pallet_nft(event) => match event {
+			Nft::CollectionCreated(collection, owner) => fields![collection, owner],
+			Nft::CollectionDestroyed(collection) => fields![collection],
+			Nft::Minted(collection, item, owner) | Nft::Burned(collection, item, owner) => {
+				fields![collection, item, owner]
+			},
+			Nft::Transferred(collection, item, from, to) => fields![collection, item, from, to],
+			Nft::MetadataSet(collection, item) | Nft::MetadataCleared(collection, item) => {
+				fields![collection, item]
+			},
+			_ => return None,
+		},
+		Event::pallet_escrow(event) => match event {
+			Escrow::Created(escrow, payer, payee, arbiter, amount, expiry) => {
+				fields![escrow, payer, payee, arbiter, amount, expiry]
+			},
+			Escrow::Released(escrow, who, amount)
+				| Escrow::Refunded(escrow, who, amount)
+				| Escrow::Expired(escrow, who, amount) => fields![escrow, who, amount],
+			_ => return None,
+		},
+		Event::pallet_oracle(event) => match event {
+			Oracle::FeederAdded(who) | Oracle::FeederRemoved(who) => fields![who],
+			Oracle::NewFeedData(who, values) => fields![who, values],
+			Oracle::NewAggregatedValue(key, value) => fields![key, value],
+			_ => return None,
+		},
+		Event::pallet_commit_reveal(event) => match event {
+			CommitReveal::Joined(who, amount) | CommitReveal::Slashed(who, amount) => {
+				fields![who, amount]
+			},
+			CommitReveal::Left(who) | CommitReveal::Revealed(who) => fields![who],
+			CommitReveal::Committed(who, commitment) => fields![who, commitment],
+			CommitReveal::NewSeed(seed) => fields![seed],
+			_ => return None,
+		},
+		Event::pallet_template(event) => match event {
+			Template::SomethingStored(something, who) => fields![something, who],
+			_ => return None,
+		},
+		Event::pallet_assets(event) => match event {
+			Assets::Created(asset, creator, owner) => fields![asset, creator, owner],
+			Assets::Issued(asset, who, amount) | Assets::Burned(asset, who, amount) => {
+				fields![asset, who, amount]
+			},
+			Assets::Transferred(asset, from, to, amount) => fields![asset, from, to, amount],
+			Assets::TeamChanged(asset, issuer, admin, freezer) => {
+				fields![asset, issuer, admin, freezer]
+			},
+			Assets::OwnerChanged(asset, who)
+				| Assets::Frozen(asset, who)
+				| Assets::Thawed(asset, who)
+				| Assets::ForceCreated(asset, who) => fields![asset, who],
+			Assets::AssetFrozen(asset)
+				| Assets::AssetThawed(asset)
+				| Assets::Destroyed(asset)
+				| Assets::MetadataCleared(asset)
+				| Assets::AssetStatusChanged(asset) => fields![asset],
+			Assets::MetadataSet(asset, name, symbol, decimals, is_frozen) => fields![
+				asset,
+				String::from_utf8_lossy(name).into_owned(),
+				String::from_utf8_lossy(symbol).into_owned(),
+				decimals,
+				is_frozen,
+			],
+			Assets::ApprovedTransfer(asset, owner, delegate, amount) => {
+				fields![asset, owner, delegate, amount]
+			},
+			Assets::ApprovalCancelled(asset, owner, delegate) => fields![asset, owner, delegate],
+			Assets::TransferredApproved(asset, owner, delegate, dest, amount) => {
+				fields![asset, owner, delegate, dest, amount]
+			},
+			_ => return None,
+		},
+	})
+}
+
+/// The event variants of the runtime, by pallet index and variant index.
+///
+/// These are the first two bytes of an encoded event.
+pub struct EventDescriptions(HashMap<(u8, u8), EventDescription>);
+
+/// An event as written to the output.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexedEvent<'a> {
+	/// The number of the block the event was emitted in.
+	pub block: u32,
+	/// The hash of that block.
+	pub block_hash: Hash,
+	/// The index of the extrinsic which emitted the event, if any.
+	pub extrinsic: Option<u32>,
+	/// The phase of the block the event was emitted in.
+	pub phase: &'static str,
+	/// The name of the pallet emitting the event.
+	pub pallet: &'a str,
+	/// The name of the variant.
+	pub variant: &'a str,
+	/// The fields of the event, with their type names.
+	pub fields: Vec<Value>,
+}
+
+impl EventDescriptions {
+	/// The event variants of the native runtime.
+	pub fn of_runtime() -> Self {
+		let mut descriptions = HashMap::new();
+		for module in runtime_modules() {
+			let pallet = decoded(module.name);
+			for (index, event) in module.event.map(decoded).unwrap_or_default().into_iter().enumerate() {
+				descriptions.insert((module.index, index as u8), EventDescription {
+					pallet: pallet.clone(),
+					variant: decoded(event.name),
+					fields: decoded(event.arguments),
+				});
+			}
+		}
+		Self(descriptions)
+	}
+
+	/// Describe an event emitted in block `number`.
+	///
+	/// The fields of the events the node doesn't know are written as the hex of their encoding.
+	pub fn index<'a>(
+		&'a self,
+		number: u32,
+		hash: Hash,
+		record: &EventRecord<Event, Hash>,
+	) -> IndexedEvent<'a> {
+		let (extrinsic, phase) = match record.phase {
+			Phase::ApplyExtrinsic(index) => (Some(index), "applyExtrinsic"),
+			Phase::Finalization => (None, "finalization"),
+			Phase::Initialization => (None, "initialization"),
+		};
+		let encoded = record.event.encode();
+		let (pallet, variant, fields) = match self.0.get(&(encoded[0], encoded[1])) {
+			Some(description) => {
+				let fields = match event_fields(&record.event) {
+					Some(values) => description.fields.iter().zip(values)
+						.map(|(name, value)| json!({ "type": name, "value": value }))
+						.collect(),
+					None => vec![json!({ "raw": to_hex(&encoded[2..], false) })],
+				};
+				(description.pallet.as_str(), description.variant.as_str(), fields)
+			},
+			None => ("", "", vec![json!({ "raw": to_hex(&encoded, false) })]),
+		};
+
+		IndexedEvent { block: number, block_hash: hash, extrinsic, phase, pallet, variant, fields }
+	}
+}
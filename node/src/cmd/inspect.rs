@@ -0,0 +1,234 @@
+//! The `inspect` subcommand, decoding extrinsics, blocks and storage keys with the types of the
+//! native runtime.
+
+use std::fmt;
+
+use codec::{Decode, Encode};
+use node_template_runtime::{
+	Address, Balance, Block, BlockNumber, Hash, Index, SignedExtra, UncheckedExtrinsic,
+};
+use sc_cli::{CliConfiguration, DatabaseParams, PruningParams, SharedParams};
+use sc_client_api::BlockBackend;
+use sc_service::{Configuration, PartialComponents};
+use sp_core::{bytes::{from_hex, to_hex}, crypto::Ss58Codec};
+use sp_runtime::{
+	generic::{BlockId, Era},
+	traits::{BlakeTwo256, Block as BlockT, Hash as _, Header as _},
+	MultiAddress,
+};
+use structopt::StructOpt;
+
+use super::storage::StorageItems;
+use crate::service;
+
+/// Decode extrinsics, blocks and storage keys with the types of the native runtime.
+#[derive(Debug, StructOpt)]
+pub struct InspectCmd {
+	#[allow(missing_docs)]
+	#[structopt(subcommand)]
+	pub command: InspectSubcommand,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub pruning_params: PruningParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub database_params: DatabaseParams,
+}
+
+/// What to inspect.
+#[derive(Debug, StructOpt)]
+pub enum InspectSubcommand {
+	/// Decode an extrinsic: its signer, signed extensions and call.
+	Extrinsic {
+		/// The SCALE encoded extrinsic, in hex.
+		#[structopt(value_name = "HEX")]
+		input: String,
+	},
+
+	/// Decode a block and its extrinsics.
+	Block {
+		/// The number or hash of a block in the local database, or a SCALE encoded block in hex.
+		#[structopt(value_name = "BLOCK")]
+		input: String,
+	},
+
+	/// Name the pallet and storage item of a storage key, and decode the keys it contains.
+	StorageKey {
+		/// The storage key, in hex.
+		#[structopt(value_name = "HEX")]
+		key: String,
+	},
+}
+
+impl InspectCmd {
+	/// Run the command, reading blocks from the database of `config` if needed.
+	pub fn run(&self, config: Configuration) -> sc_cli::Result<()> {
+		match &self.command {
+			InspectSubcommand::Extrinsic { input } => {
+				let extrinsic = decode_hex::<UncheckedExtrinsic>(input, "extrinsic")?;
+				print!("{}", ExtrinsicDisplay(&extrinsic));
+			},
+			InspectSubcommand::Block { input } => {
+				print!("{}", BlockDisplay(&load_block(input, &config)?));
+			},
+			InspectSubcommand::StorageKey { key } => {
+				let key = from_hex(key).map_err(|e| format!("Invalid hex storage key: {}", e))?;
+				print!("{}", StorageKeyDisplay(&StorageItems::of_runtime(), &key));
+			},
+		}
+		Ok(())
+	}
+}
+
+impl CliConfiguration for InspectCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn pruning_params(&self) -> Option<&PruningParams> {
+		Some(&self.pruning_params)
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
+
+/// Decode a value of type `T` from hex, `what` naming it in errors.
+fn decode_hex<T: Decode>(input: &str, what: &str) -> sc_cli::Result<T> {
+	let bytes = from_hex(input).map_err(|e| format!("Invalid hex {}: {}", what, e))?;
+	let mut input = &bytes[..];
+	let value = T::decode(&mut input).map_err(|e| format!("Invalid {}: {}", what, e))?;
+	if !input.is_empty() {
+		return Err(format!("Invalid {}: {} trailing bytes", what, input.len()).into())
+	}
+	Ok(value)
+}
+
+/// Load the block `input` refers to: a number or hash from the database, or an encoded block.
+fn load_block(input: &str, config: &Configuration) -> sc_cli::Result<Block> {
+	let id = if let Ok(number) = input.parse::<BlockNumber>() {
+		BlockId::Number(number)
+	} else if input.trim_start_matches("0x").len() == 64 {
+		BlockId::Hash(decode_hex::<Hash>(input, "block hash")?)
+	} else {
+		return decode_hex(input, "block")
+	};
+
+	let PartialComponents { client, .. } = service::new_partial(config)?;
+	let block = client.block(&id)?
+		.ok_or_else(|| format!("Block {} is not in the database", id))?
+		.block;
+	// Opaque extrinsics are encoded like the extrinsics they hold.
+	Block::decode(&mut &block.encode()[..]).map_err(|e| format!("Invalid block: {}", e).into())
+}
+
+/// The fields of `SignedExtra` included in extrinsics, in the order they are encoded.
+///
+/// The other signed extensions only sign data of the chain, like its genesis hash.
+#[derive(Decode)]
+struct ExtraFields {
+	era: Era,
+	#[codec(compact)]
+	nonce: Index,
+	#[codec(compact)]
+	tip: Balance,
+}
+
+impl ExtraFields {
+	fn of(extra: &SignedExtra) -> Self {
+		Self::decode(&mut &extra.encode()[..])
+			.expect("`SignedExtra` encodes the era, the nonce and the tip in that order; qed")
+	}
+}
+
+/// Writes an extrinsic in a readable form.
+pub struct ExtrinsicDisplay<'a>(pub &'a UncheckedExtrinsic);
+
+impl fmt::Display for ExtrinsicDisplay<'_> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let extrinsic = self.0;
+		writeln!(f, "Hash: {:?}", BlakeTwo256::hash(&extrinsic.encode()))?;
+		match &extrinsic.signature {
+			Some((address, signature, extra)) => {
+				let extra = ExtraFields::of(extra);
+				writeln!(f, "Signer: {}", AddressDisplay(address))?;
+				writeln!(f, "Signature: {:?}", signature)?;
+				match extra.era {
+					Era::Immortal => writeln!(f, "Era: immortal")?,
+					Era::Mortal(period, phase) => {
+						writeln!(f, "Era: mortal, period {}, phase {}", period, phase)?
+					},
+				}
+				writeln!(f, "Nonce: {}", extra.nonce)?;
+				writeln!(f, "Tip: {}", extra.tip)?;
+			},
+			None => writeln!(f, "Unsigned")?,
+		}
+		writeln!(f, "Call: {:#?}", extrinsic.function)
+	}
+}
+
+/// Writes an address as SS58 when it is an account id.
+struct AddressDisplay<'a>(&'a Address);
+
+impl fmt::Display for AddressDisplay<'_> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self.0 {
+			MultiAddress::Id(account) => write!(f, "{}", account.to_ss58check()),
+			address => write!(f, "{:?}", address),
+		}
+	}
+}
+
+/// Writes a block and its extrinsics in a readable form.
+pub struct BlockDisplay<'a>(pub &'a Block);
+
+impl fmt::Display for BlockDisplay<'_> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let header = self.0.header();
+		writeln!(f, "Block #{} ({:?})", header.number(), header.hash())?;
+		writeln!(f, "Parent hash: {:?}", header.parent_hash())?;
+		writeln!(f, "State root: {:?}", header.state_root())?;
+		writeln!(f, "Extrinsics root: {:?}", header.extrinsics_root())?;
+		for log in header.digest().logs() {
+			writeln!(f, "Digest: {:?}", log)?;
+		}
+		for (index, extrinsic) in self.0.extrinsics().iter().enumerate() {
+			writeln!(f)?;
+			writeln!(f, "Extrinsic #{}", index)?;
+			write!(f, "{}", ExtrinsicDisplay(extrinsic))?;
+		}
+		Ok(())
+	}
+}
+
+/// Writes the pallet and storage item of a storage key, and the keys it contains.
+pub struct StorageKeyDisplay<'a>(pub &'a StorageItems, pub &'a [u8]);
+
+impl fmt::Display for StorageKeyDisplay<'_> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let (items, key) = (self.0, self.1);
+		if key.starts_with(b":") {
+			return writeln!(f, "Well known key: {}", String::from_utf8_lossy(key))
+		}
+		if let Some(item) = items.item(key) {
+			writeln!(f, "Storage item: {}::{}", item.pallet, item.item)?;
+			writeln!(f, "Value type: {}", item.value)?;
+			for decoded in item.decode_keys(&key[32..]) {
+				writeln!(f, "Key: {}", decoded)?;
+			}
+			return Ok(())
+		}
+		match items.pallet(key) {
+			Some(pallet) => writeln!(f, "Pallet: {}, unknown item {}", pallet, to_hex(&key[16..], false)),
+			None => writeln!(f, "Unknown storage key"),
+		}
+	}
+}
@@ -0,0 +1,23 @@
+//! Subcommands specific to this node.
+
+mod db_stats;
+pub mod decode;
+mod fork_off;
+mod index_events;
+mod inspect;
+pub mod storage;
+mod tx;
+
+pub use db_stats::{state_usage, DbStatsCmd, Usage};
+pub use fork_off::{fork_off, ForkOffCmd};
+pub use index_events::{
+	event_fields, EventDescription, EventDescriptions, IndexEventsCmd, IndexedEvent,
+};
+pub use inspect::{
+	BlockDisplay, ExtrinsicDisplay, InspectCmd, InspectSubcommand, StorageKeyDisplay,
+};
+pub use tx::{
+	extrinsic, sign, signed_extra, signed_payload, ChainState, Node, PrepareCmd, RpcParams,
+	SendCmd, SignCmd, SignedTransaction, SubmitCmd, TransactionParams, TxCall, TxCmd,
+	TxSubcommand, UnsignedTransaction,
+};
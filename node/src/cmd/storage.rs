@@ -0,0 +1,218 @@
+//! Naming of storage keys after the pallets and storage items of the runtime.
+
+use std::collections::HashMap;
+
+use frame_metadata::{StorageEntryType, StorageHasher};
+use node_template_runtime::{AccountId, AccountIndex, AssetId, BlockNumber, Hash};
+use pallet_escrow::EscrowId;
+use pallet_nft::{CollectionId, ItemId};
+use serde_json::{json, Value};
+use sp_core::{bytes::to_hex, twox_128};
+
+use super::decode::{decode_json, decoded, runtime_modules};
+
+/// Decodes a key of a storage item, as JSON.
+type KeyDecoder = fn(&mut &[u8]) -> Result<Value, codec::Error>;
+
+/// The native types of the keys of the storage maps of the runtime, by the storage prefix of
+/// their pallet and their name.
+///
+/// Keys of the items not listed here are left encoded, and labelled as such by
+/// `StorageItem::decode_keys`; `StorageItems::undecoded` lists the items of the runtime missing
+/// from here.
+fn key_decoders(pallet: &str, item: &str) -> Vec<KeyDecoder> {
+	let account: KeyDecoder = decode_json::<AccountId>;
+	let account_index: KeyDecoder = decode_json::<AccountIndex>;
+	let asset: KeyDecoder = decode_json::<AssetId>;
+	let block: KeyDecoder = decode_json::<BlockNumber>;
+	let collection: KeyDecoder = decode_json::<CollectionId>;
+	let escrow: KeyDecoder = decode_json::<EscrowId>;
+	let hash: KeyDecoder = decode_json::<Hash>;
+	let item_id: KeyDecoder = decode_json::<ItemId>;
+	let owned_item: KeyDecoder = decode_json::<(CollectionId, ItemId)>;
+	let proposal: KeyDecoder = decode_json::<pallet_treasury::ProposalIndex>;
+	let index: KeyDecoder = decode_json::<u32>;
+	let set_id: KeyDecoder = decode_json::<sp_finality_grandpa::SetId>;
+	// `pallet_assets::ApprovalKey` encodes as its owner followed by its delegate.
+	let approval: KeyDecoder = decode_json::<(AccountId, AccountId)>;
+
+	match (pallet, item) {
+		("System", "Account")
+			| ("Balances", "Account")
+			| ("Balances", "Locks")
+			| ("Balances", "Reserves")
+			| ("Identity", "IdentityOf")
+			| ("Identity", "SuperOf")
+			| ("Identity", "SubsOf")
+			| ("AssetFees", "FeeAsset")
+			| ("CommitReveal", "Participants")
+			| ("CommitReveal", "Commitments")
+			| ("TemplateModule", "LastFreeCall") => vec![account],
+		("System", "BlockHash") | ("Escrow", "Expiring") => vec![block],
+		("System", "ExtrinsicData") => vec![index],
+		("System", "EventTopics") => vec![hash],
+		("Grandpa", "SetIdSession") => vec![set_id],
+		("Indices", "Accounts") => vec![account_index],
+		("Treasury", "Proposals") => vec![proposal],
+		("Assets", "Asset")
+			| ("Assets", "Metadata")
+			| ("AssetFees", "ConversionRate")
+			| ("Oracle", "AggregatedValues") => vec![asset],
+		("Assets", "Account") | ("Oracle", "RawValues") => vec![asset, account],
+		("Assets", "Approvals") => vec![asset, approval],
+		("Nft", "Collections") => vec![collection],
+		("Nft", "Items") | ("Nft", "Metadata") => vec![collection, item_id],
+		("Nft", "Owned") => vec![account, owned_item],
+		("Escrow", "Escrows") => vec![escrow],
+		_ => Vec::new(),
+	}
+}
+
+/// A storage item of the runtime, as described by its metadata.
+#[derive(Debug)]
+pub struct StorageItem {
+	/// The storage prefix of the pallet.
+	pub pallet: String,
+	/// The name of the item.
+	pub item: String,
+	/// The hashers and type names of the keys of the item, none for storage values.
+	pub keys: Vec<(StorageHasher, String)>,
+	/// The type name of the values.
+	pub value: String,
+	/// The decoders of the keys, as many as the node knows the native types of.
+	decoders: Vec<KeyDecoder>,
+}
+
+impl StorageItem {
+	/// Whether the node knows the native types of all the keys of the item.
+	pub fn decodes_keys(&self) -> bool {
+		self.decoders.len() >= self.keys.len()
+	}
+
+	/// Decode the keys of the item from what follows its prefix in a storage key.
+	///
+	/// Keys hashed without concatenation can't be recovered and are written as their hash.
+	/// Keys the node doesn't know the native type of, or that fail to decode as it, are written
+	/// as the hex of the remaining input, along with the keys after them, and labelled
+	/// `undecoded` with the reason.
+	pub fn decode_keys(&self, mut input: &[u8]) -> Vec<Value> {
+		let mut keys = Vec::with_capacity(self.keys.len());
+		for (index, (hasher, name)) in self.keys.iter().enumerate() {
+			if input.is_empty() {
+				break
+			}
+
+			let (hash_len, concat) = hasher_layout(hasher);
+			let hasher = format!("{:?}", hasher);
+			if input.len() < hash_len {
+				keys.push(json!({
+					"hasher": hasher,
+					"type": name,
+					"raw": to_hex(input, false),
+					"undecoded": "truncated key",
+				}));
+				break
+			}
+			let (hash, rest) = input.split_at(hash_len);
+			input = rest;
+			if !concat {
+				keys.push(json!({ "hasher": hasher, "type": name, "hash": to_hex(hash, false) }));
+				continue
+			}
+
+			let remaining = input;
+			let undecoded = match self.decoders.get(index).map(|decode| decode(&mut input)) {
+				Some(Ok(value)) => {
+					keys.push(json!({ "hasher": hasher, "type": name, "value": value }));
+					continue
+				},
+				Some(Err(e)) => format!("{}", e),
+				None => "no native type known".into(),
+			};
+			keys.push(json!({
+				"hasher": hasher,
+				"type": name,
+				"raw": to_hex(remaining, false),
+				"undecoded": undecoded,
+			}));
+			break
+		}
+		keys
+	}
+}
+
+/// The length of the hash a hasher prefixes keys with, and whether the key follows it.
+fn hasher_layout(hasher: &StorageHasher) -> (usize, bool) {
+	match hasher {
+		StorageHasher::Blake2_128 | StorageHasher::Twox128 => (16, false),
+		StorageHasher::Blake2_256 | StorageHasher::Twox256 => (32, false),
+		StorageHasher::Blake2_128Concat => (16, true),
+		StorageHasher::Twox64Concat => (8, true),
+		StorageHasher::Identity => (0, true),
+	}
+}
+
+/// The storage items of the runtime, by the 32 bytes prefixing their keys.
+pub struct StorageItems {
+	items: HashMap<Vec<u8>, StorageItem>,
+	pallets: HashMap<Vec<u8>, String>,
+}
+
+impl StorageItems {
+	/// The storage items of the native runtime.
+	pub fn of_runtime() -> Self {
+		let mut items = HashMap::new();
+		let mut pallets = HashMap::new();
+		for storage in runtime_modules().into_iter().filter_map(|module| module.storage.map(decoded)) {
+			let pallet = decoded(storage.prefix);
+			let pallet_prefix = twox_128(pallet.as_bytes());
+			pallets.insert(pallet_prefix.to_vec(), pallet.clone());
+
+			for entry in decoded(storage.entries) {
+				let (keys, value) = match entry.ty {
+					StorageEntryType::Plain(value) => (Vec::new(), value),
+					StorageEntryType::Map { hasher, key, value, .. } => {
+						(vec![(hasher, decoded(key))], value)
+					},
+					StorageEntryType::DoubleMap { hasher, key1, key2, value, key2_hasher } => {
+						(vec![(hasher, decoded(key1)), (key2_hasher, decoded(key2))], value)
+					},
+					StorageEntryType::NMap { keys, hashers, value } => {
+						(decoded(hashers).into_iter().zip(decoded(keys)).collect(), value)
+					},
+				};
+				let item = decoded(entry.name);
+				let prefix = [pallet_prefix, twox_128(item.as_bytes())].concat();
+				items.insert(prefix, StorageItem {
+					decoders: key_decoders(&pallet, &item),
+					pallet: pallet.clone(),
+					item,
+					keys,
+					value: decoded(value),
+				});
+			}
+		}
+		Self { items, pallets }
+	}
+
+	/// The storage item `key` belongs to, if any.
+	pub fn item(&self, key: &[u8]) -> Option<&StorageItem> {
+		key.get(..32).and_then(|prefix| self.items.get(prefix))
+	}
+
+	/// The items of the runtime the node doesn't know the native types of the keys of, as
+	/// `pallet::item`.
+	pub fn undecoded(&self) -> Vec<String> {
+		let mut undecoded: Vec<_> = self.items.values()
+			.filter(|item| !item.decodes_keys())
+			.map(|item| format!("{}::{}", item.pallet, item.item))
+			.collect();
+		undecoded.sort();
+		undecoded
+	}
+
+	/// The storage prefix of the pallet `key` belongs to, if any.
+	pub fn pallet(&self, key: &[u8]) -> Option<&str> {
+		key.get(..16).and_then(|prefix| self.pallets.get(prefix)).map(String::as_str)
+	}
+}
@@ -0,0 +1,613 @@
+//! The `tx` subcommand, signing transactions and submitting them to a node.
+//!
+//! Transactions can be signed and submitted in one go with `tx send`, or in three steps for keys
+//! kept on an offline host: `tx prepare` writes what is signed to a file, `tx sign` signs it
+//! without a node, and `tx submit` submits the signed transaction.
+
+use std::{convert::TryFrom, fs, path::{Path, PathBuf}};
+
+use codec::{Decode, Encode};
+use futures::{compat::{Future01CompatExt, Stream01CompatExt}, StreamExt, TryFutureExt};
+use jsonrpc_core_client::{transports::ws, RpcChannel, RpcError};
+use node_template_runtime::{
+	extensions::ChargeTransactionPaymentByTip, opaque, pallet_template, AccountId, AccountIndex,
+	Address, Balance, BalancesCall, BlockNumber, Call, Hash, Header, Index, SignedExtra,
+	UncheckedExtrinsic,
+};
+use sc_cli::{CliConfiguration, SharedParams};
+use sc_keystore::LocalKeystore;
+use sc_rpc_api::{author::AuthorClient, chain::ChainClient, state::StateClient};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sp_core::{
+	bytes::to_hex,
+	crypto::{CryptoTypePublicPair, KeyTypeId, SecretString, Ss58Codec},
+	sr25519, Bytes, Pair,
+};
+use sp_keystore::SyncCryptoStore;
+use sp_rpc::{list::ListOrValue, number::NumberOrHex};
+use sp_runtime::{
+	generic::{Era, SignedPayload, SignedBlock},
+	MultiAddress, MultiSignature,
+};
+use sp_transaction_pool::TransactionStatus;
+use structopt::StructOpt;
+use substrate_frame_rpc_system::SystemClient;
+
+/// Sign transactions and submit them to a node over WebSocket.
+#[derive(Debug, StructOpt)]
+pub struct TxCmd {
+	#[allow(missing_docs)]
+	#[structopt(subcommand)]
+	pub command: TxSubcommand,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+}
+
+/// The steps of sending a transaction.
+#[derive(Debug, StructOpt)]
+pub enum TxSubcommand {
+	/// Sign a transaction with a secret URI and submit it.
+	Send(SendCmd),
+
+	/// Write what a transaction signs to a file, to sign it on another host with `tx sign`.
+	Prepare(PrepareCmd),
+
+	/// Sign a transaction written by `tx prepare`, without connecting to a node.
+	Sign(SignCmd),
+
+	/// Submit a transaction signed by `tx sign`.
+	Submit(SubmitCmd),
+}
+
+/// Sign a transaction with a secret URI and submit it.
+#[derive(Debug, StructOpt)]
+pub struct SendCmd {
+	#[allow(missing_docs)]
+	#[structopt(subcommand)]
+	pub call: TxCall,
+
+	/// The secret URI of the sr25519 key signing the transaction, e.g. `//Alice`.
+	#[structopt(long, value_name = "SURI")]
+	pub suri: String,
+
+	/// The password of the secret URI, if any.
+	#[structopt(long)]
+	pub password: Option<String>,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub transaction: TransactionParams,
+
+	/// Print the signed transaction in hex instead of submitting it.
+	#[structopt(long)]
+	pub print_only: bool,
+
+	/// Wait until the transaction is finalized, rather than included in a block.
+	#[structopt(long)]
+	pub wait_finalized: bool,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub rpc: RpcParams,
+}
+
+/// Write what a transaction signs to a file.
+#[derive(Debug, StructOpt)]
+pub struct PrepareCmd {
+	#[allow(missing_docs)]
+	#[structopt(subcommand)]
+	pub call: TxCall,
+
+	/// The SS58 address of the account signing the transaction.
+	#[structopt(long, parse(try_from_str = parse_account), value_name = "ADDRESS")]
+	pub signer: AccountId,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub transaction: TransactionParams,
+
+	/// The file the unsigned transaction is written to.
+	#[structopt(long, parse(from_os_str), value_name = "FILE")]
+	pub out: PathBuf,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub rpc: RpcParams,
+}
+
+/// Sign a transaction written by `tx prepare`.
+#[derive(Debug, StructOpt)]
+pub struct SignCmd {
+	/// The file written by `tx prepare`.
+	#[structopt(parse(from_os_str), value_name = "FILE")]
+	pub payload: PathBuf,
+
+	/// The secret URI of the sr25519 key of the signer.
+	#[structopt(long, value_name = "SURI", conflicts_with = "keystore-path")]
+	pub suri: Option<String>,
+
+	/// The keystore holding the sr25519 key of the signer.
+	#[structopt(long, parse(from_os_str), value_name = "PATH", required_unless = "suri")]
+	pub keystore_path: Option<PathBuf>,
+
+	/// The key type the key of the signer is stored under in the keystore.
+	#[structopt(
+		long,
+		default_value = "acco",
+		parse(try_from_str = parse_key_type),
+		value_name = "KEY_TYPE",
+	)]
+	pub key_type: KeyTypeId,
+
+	/// The password of the secret URI or of the keystore, if any.
+	#[structopt(long)]
+	pub password: Option<String>,
+
+	/// The file the signed transaction is written to.
+	#[structopt(long, parse(from_os_str), value_name = "FILE")]
+	pub out: PathBuf,
+}
+
+/// Submit a transaction signed by `tx sign`.
+#[derive(Debug, StructOpt)]
+pub struct SubmitCmd {
+	/// The file written by `tx sign`.
+	#[structopt(parse(from_os_str), value_name = "FILE")]
+	pub transaction: PathBuf,
+
+	/// Wait until the transaction is finalized, rather than included in a block.
+	#[structopt(long)]
+	pub wait_finalized: bool,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub rpc: RpcParams,
+}
+
+/// The parameters of a transaction besides its call.
+#[derive(Debug, StructOpt)]
+pub struct TransactionParams {
+	/// The tip paid to the block author on top of the fee.
+	#[structopt(long, default_value = "0", value_name = "BALANCE")]
+	pub tip: Balance,
+
+	/// The number of blocks the transaction is valid for, from the last finalized block.
+	///
+	/// Rounded to a power of two between 4 and 65536. `0` makes the transaction immortal.
+	/// As the period starts at the last finalized block, it must outlast how far finality lags
+	/// behind the best block, or the transaction is outdated by the time it is sent. As the
+	/// runtime only keeps the hashes of the last 2400 blocks, periods longer than 2048 are cut
+	/// short to that many blocks.
+	#[structopt(long, default_value = "2048", value_name = "BLOCKS")]
+	pub mortality: u64,
+
+	/// The nonce of the transaction. Defaults to the next nonce of the signer, accounting for
+	/// its transactions in the pool of the node.
+	#[structopt(long)]
+	pub nonce: Option<Index>,
+}
+
+/// The node transactions are sent to.
+#[derive(Debug, StructOpt)]
+pub struct RpcParams {
+	/// The WebSocket RPC endpoint of the node.
+	#[structopt(long, default_value = "ws://127.0.0.1:9944", value_name = "URL")]
+	pub url: String,
+}
+
+/// The calls `tx` can make.
+#[derive(Debug, Clone, StructOpt)]
+pub enum TxCall {
+	/// Store a value with `pallet_template::do_something`.
+	DoSomething {
+		/// The value to store.
+		something: u32,
+	},
+
+	/// Transfer a balance with `pallet_balances::transfer`.
+	Transfer {
+		/// The recipient, as an SS58 address or as an account index of `pallet_indices`.
+		#[structopt(parse(try_from_str = parse_address))]
+		dest: Address,
+		/// The amount transferred.
+		value: Balance,
+	},
+}
+
+impl TxCall {
+	/// The runtime call this makes.
+	pub fn to_call(&self) -> Call {
+		match self {
+			Self::DoSomething { something } => {
+				Call::TemplateModule(pallet_template::Call::do_something(*something))
+			},
+			Self::Transfer { dest, value } => {
+				Call::Balances(BalancesCall::transfer(dest.clone(), *value))
+			},
+		}
+	}
+}
+
+fn parse_account(address: &str) -> Result<AccountId, String> {
+	AccountId::from_ss58check(address).map_err(|e| format!("Invalid address: {:?}", e))
+}
+
+fn parse_address(address: &str) -> Result<Address, String> {
+	match address.parse::<AccountIndex>() {
+		Ok(index) => Ok(MultiAddress::Index(index)),
+		Err(_) => parse_account(address).map(MultiAddress::Id),
+	}
+}
+
+fn parse_key_type(key_type: &str) -> Result<KeyTypeId, String> {
+	KeyTypeId::try_from(key_type)
+		.map_err(|_| format!("Invalid key type {}, expected 4 characters", key_type))
+}
+
+impl TxCmd {
+	/// Run the command.
+	pub fn run(&self) -> sc_cli::Result<()> {
+		match &self.command {
+			TxSubcommand::Send(cmd) => block_on(cmd.run()),
+			TxSubcommand::Prepare(cmd) => block_on(cmd.run()),
+			TxSubcommand::Sign(cmd) => cmd.run(),
+			TxSubcommand::Submit(cmd) => block_on(cmd.run()),
+		}
+	}
+}
+
+impl CliConfiguration for TxCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+}
+
+/// Run `future`, which talks to a node.
+fn block_on(future: impl std::future::Future<Output = sc_cli::Result<()>>) -> sc_cli::Result<()> {
+	// The RPC clients are built on futures 0.1, and spawn their connection on tokio 0.1.
+	let mut runtime = tokio01::runtime::current_thread::Runtime::new()?;
+	runtime.block_on(Box::pin(future).compat())
+}
+
+impl SendCmd {
+	async fn run(&self) -> sc_cli::Result<()> {
+		let pair = sr25519::Pair::from_string(&self.suri, self.password.as_deref())
+			.map_err(|e| format!("Invalid secret URI: {:?}", e))?;
+		let node = Node::connect(&self.rpc.url).await?;
+		let transaction = self.transaction.prepare(&node, pair.public().into(), &self.call).await?;
+		let extrinsic = sign(
+			&pair,
+			transaction.call()?,
+			transaction.nonce,
+			transaction.tip,
+			&transaction.chain,
+		);
+
+		if self.print_only {
+			println!("{}", to_hex(&extrinsic.encode(), false));
+			return Ok(())
+		}
+		node.submit_and_watch(&extrinsic, self.wait_finalized).await
+	}
+}
+
+impl PrepareCmd {
+	async fn run(&self) -> sc_cli::Result<()> {
+		let node = Node::connect(&self.rpc.url).await?;
+		let transaction = self.transaction.prepare(&node, self.signer.clone(), &self.call).await?;
+		write_json(&self.out, &transaction)
+	}
+}
+
+impl SignCmd {
+	fn run(&self) -> sc_cli::Result<()> {
+		let transaction: UnsignedTransaction = read_json(&self.payload)?;
+		let call = transaction.call()?;
+		// The signer can't check the call on the offline host otherwise.
+		eprintln!("Signing as {}: {:#?}", transaction.signer.to_ss58check(), call);
+		let payload = signed_payload(call, transaction.nonce, transaction.tip, &transaction.chain);
+
+		let signature = match (&self.suri, &self.keystore_path) {
+			(Some(suri), _) => {
+				let pair = sr25519::Pair::from_string(suri, self.password.as_deref())
+					.map_err(|e| format!("Invalid secret URI: {:?}", e))?;
+				if AccountId::from(pair.public()) != transaction.signer {
+					return Err("The secret URI is not the key of the signer".into())
+				}
+				payload.using_encoded(|payload| pair.sign(payload))
+			},
+			(None, Some(path)) => {
+				let keystore = LocalKeystore::open(path, self.password.clone().map(SecretString::new))
+					.map_err(|e| format!("Cannot open the keystore: {}", e))?;
+				let key = CryptoTypePublicPair(sr25519::CRYPTO_ID, transaction.signer.as_ref().to_vec());
+				let signature = payload.using_encoded(|payload| {
+					SyncCryptoStore::sign_with(&keystore, self.key_type, &key, payload)
+				}).map_err(|e| format!("Cannot sign with the keystore: {}", e))?;
+				sr25519::Signature::decode(&mut &signature[..])
+					.map_err(|e| format!("Invalid signature from the keystore: {}", e))?
+			},
+			(None, None) => return Err("Either --suri or --keystore-path is required".into()),
+		};
+
+		write_json(&self.out, &SignedTransaction {
+			extrinsic: Bytes(extrinsic(payload, transaction.signer, signature.into()).encode()),
+			genesis_hash: transaction.chain.genesis_hash,
+			spec_version: transaction.chain.spec_version,
+			transaction_version: transaction.chain.transaction_version,
+		})
+	}
+}
+
+impl SubmitCmd {
+	async fn run(&self) -> sc_cli::Result<()> {
+		let transaction: SignedTransaction = read_json(&self.transaction)?;
+		let extrinsic = UncheckedExtrinsic::decode(&mut &transaction.extrinsic[..])
+			.map_err(|e| format!("Invalid signed transaction: {}", e))?;
+
+		let node = Node::connect(&self.rpc.url).await?;
+		// The node would only reject the transaction as having a bad signature.
+		let live = node.chain_state(0).await?;
+		if live.genesis_hash != transaction.genesis_hash {
+			return Err(format!(
+				"The transaction is for the chain with genesis {:?}, the node is on {:?}",
+				transaction.genesis_hash, live.genesis_hash,
+			).into())
+		}
+		if (live.spec_version, live.transaction_version)
+			!= (transaction.spec_version, transaction.transaction_version)
+		{
+			return Err(format!(
+				"The transaction is for spec version {} and transaction version {}, \
+				the runtime of the node is at {} and {}. Prepare it again.",
+				transaction.spec_version, transaction.transaction_version,
+				live.spec_version, live.transaction_version,
+			).into())
+		}
+		node.submit_and_watch(&extrinsic, self.wait_finalized).await
+	}
+}
+
+impl TransactionParams {
+	async fn prepare(
+		&self,
+		node: &Node,
+		signer: AccountId,
+		call: &TxCall,
+	) -> sc_cli::Result<UnsignedTransaction> {
+		let nonce = match self.nonce {
+			Some(nonce) => nonce,
+			None => node.nonce(signer.clone()).await?,
+		};
+		Ok(UnsignedTransaction {
+			signer,
+			call: Bytes(call.to_call().encode()),
+			nonce,
+			tip: self.tip,
+			chain: node.chain_state(self.mortality).await?,
+		})
+	}
+}
+
+fn read_json<T: DeserializeOwned>(path: &Path) -> sc_cli::Result<T> {
+	let file = fs::File::open(path)?;
+	serde_json::from_reader(file)
+		.map_err(|e| format!("Invalid transaction file {}: {}", path.display(), e).into())
+}
+
+fn write_json<T: Serialize>(path: &Path, value: &T) -> sc_cli::Result<()> {
+	let file = fs::File::create(path)?;
+	serde_json::to_writer_pretty(file, value)
+		.map_err(|e| format!("Cannot write {}: {}", path.display(), e).into())
+}
+
+/// What a transaction signs about the chain it is valid on.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChainState {
+	/// The hash of the genesis block.
+	pub genesis_hash: Hash,
+	/// The `spec_version` of the runtime.
+	pub spec_version: u32,
+	/// The `transaction_version` of the runtime.
+	pub transaction_version: u32,
+	/// The era the transaction is valid in.
+	pub era: Era,
+	/// The hash of the block the era starts at, the genesis for immortal transactions.
+	pub birth_hash: Hash,
+}
+
+/// A transaction to sign, as written by `tx prepare`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnsignedTransaction {
+	/// The account signing the transaction.
+	pub signer: AccountId,
+	/// The SCALE encoded call.
+	pub call: Bytes,
+	/// The nonce of the transaction.
+	pub nonce: Index,
+	/// The tip paid to the block author.
+	pub tip: Balance,
+	/// The chain the transaction is valid on.
+	#[serde(flatten)]
+	pub chain: ChainState,
+}
+
+impl UnsignedTransaction {
+	/// Decode the call of the transaction.
+	pub fn call(&self) -> sc_cli::Result<Call> {
+		Call::decode(&mut &self.call[..]).map_err(|e| format!("Invalid call: {}", e).into())
+	}
+}
+
+/// A signed transaction, as written by `tx sign`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignedTransaction {
+	/// The SCALE encoded `UncheckedExtrinsic`.
+	pub extrinsic: Bytes,
+	/// The hash of the genesis block the transaction was signed for.
+	pub genesis_hash: Hash,
+	/// The `spec_version` the transaction was signed for.
+	pub spec_version: u32,
+	/// The `transaction_version` the transaction was signed for.
+	pub transaction_version: u32,
+}
+
+/// The `SignedExtra` of a transaction.
+pub fn signed_extra(era: Era, nonce: Index, tip: Balance) -> SignedExtra {
+	(
+		frame_system::CheckSpecVersion::new(),
+		frame_system::CheckTxVersion::new(),
+		frame_system::CheckGenesis::new(),
+		frame_system::CheckEra::from(era),
+		frame_system::CheckNonce::from(nonce),
+		frame_system::CheckWeight::new(),
+		ChargeTransactionPaymentByTip::from(tip),
+		pallet_template::CheckFreeCall::new(),
+	)
+}
+
+/// What the signer of `call` signs for the chain in `chain`.
+pub fn signed_payload(
+	call: Call,
+	nonce: Index,
+	tip: Balance,
+	chain: &ChainState,
+) -> SignedPayload<Call, SignedExtra> {
+	// What the signed extensions read from the chain when checking the transaction.
+	let additional_signed = (
+		chain.spec_version,
+		chain.transaction_version,
+		chain.genesis_hash,
+		chain.birth_hash,
+		(),
+		(),
+		(),
+		(),
+	);
+	SignedPayload::from_raw(call, signed_extra(chain.era, nonce, tip), additional_signed)
+}
+
+/// The transaction `signer` signed `payload` of with `signature`.
+pub fn extrinsic(
+	payload: SignedPayload<Call, SignedExtra>,
+	signer: AccountId,
+	signature: MultiSignature,
+) -> UncheckedExtrinsic {
+	let (call, extra, _) = payload.deconstruct();
+	UncheckedExtrinsic::new_signed(call, MultiAddress::Id(signer), signature, extra)
+}
+
+/// Sign `call` with `pair` for the chain in `chain`.
+pub fn sign(
+	pair: &sr25519::Pair,
+	call: Call,
+	nonce: Index,
+	tip: Balance,
+	chain: &ChainState,
+) -> UncheckedExtrinsic {
+	let payload = signed_payload(call, nonce, tip, chain);
+	let signature = payload.using_encoded(|payload| pair.sign(payload));
+	extrinsic(payload, pair.public().into(), signature.into())
+}
+
+fn rpc_error(error: RpcError) -> sc_cli::Error {
+	format!("RPC request failed: {}", error).into()
+}
+
+/// The RPC clients of a node.
+pub struct Node {
+	author: AuthorClient<Hash, Hash>,
+	chain: ChainClient<BlockNumber, Hash, Header, SignedBlock<opaque::Block>>,
+	state: StateClient<Hash>,
+	system: SystemClient<AccountId, Index>,
+}
+
+impl Node {
+	/// Connect to the WebSocket RPC endpoint at `url`.
+	pub async fn connect(url: &str) -> sc_cli::Result<Self> {
+		let url = url::Url::parse(url).map_err(|e| format!("Invalid URL {}: {}", url, e))?;
+		let channel = ws::connect::<RpcChannel>(&url).compat().await.map_err(rpc_error)?;
+		Ok(Self {
+			author: channel.clone().into(),
+			chain: channel.clone().into(),
+			state: channel.clone().into(),
+			system: channel.into(),
+		})
+	}
+
+	/// The next nonce of `account`, accounting for its transactions in the pool.
+	pub async fn nonce(&self, account: AccountId) -> sc_cli::Result<Index> {
+		self.system.nonce(account).compat().await.map_err(rpc_error)
+	}
+
+	/// The state of the chain a transaction valid for `mortality` blocks signs.
+	///
+	/// Mortal transactions start their era at the last finalized block, which can't be
+	/// reverted.
+	pub async fn chain_state(&self, mortality: u64) -> sc_cli::Result<ChainState> {
+		let genesis = self.chain.block_hash(Some(ListOrValue::Value(NumberOrHex::Number(0))))
+			.compat()
+			.await
+			.map_err(rpc_error)?;
+		let genesis_hash = match genesis {
+			ListOrValue::Value(Some(hash)) => hash,
+			_ => return Err("The node has no genesis block".into()),
+		};
+		let version = self.state.runtime_version(None).compat().await.map_err(rpc_error)?;
+
+		let (era, birth_hash) = if mortality == 0 {
+			(Era::Immortal, genesis_hash)
+		} else {
+			let finalized = self.chain.finalized_head().compat().await.map_err(rpc_error)?;
+			let header = self.chain.header(Some(finalized)).compat().await.map_err(rpc_error)?
+				.ok_or("The node has no header for its finalized block")?;
+			(Era::mortal(mortality, header.number.into()), finalized)
+		};
+
+		Ok(ChainState {
+			genesis_hash,
+			spec_version: version.spec_version,
+			transaction_version: version.transaction_version,
+			era,
+			birth_hash,
+		})
+	}
+
+	/// Submit `extrinsic`, then wait until it is included in a block, or finalized if
+	/// `wait_finalized`.
+	pub async fn submit_and_watch(
+		&self,
+		extrinsic: &UncheckedExtrinsic,
+		wait_finalized: bool,
+	) -> sc_cli::Result<()> {
+		let mut statuses = self.author.watch_extrinsic(Bytes(extrinsic.encode()))
+			.compat()
+			.await
+			.map_err(rpc_error)?
+			.compat();
+		while let Some(status) = statuses.next().await {
+			match status.map_err(rpc_error)? {
+				TransactionStatus::InBlock(block) => {
+					println!("Included in block {:?}", block);
+					if !wait_finalized {
+						return Ok(())
+					}
+				},
+				TransactionStatus::Finalized(block) => {
+					println!("Finalized in block {:?}", block);
+					return Ok(())
+				},
+				TransactionStatus::Retracted(block) => println!("Block {:?} was retracted", block),
+				TransactionStatus::Future => println!("Waiting for a lower nonce"),
+				TransactionStatus::Ready => println!("Ready"),
+				TransactionStatus::Broadcast(peers) => println!("Broadcast to {} peers", peers.len()),
+				status => return Err(format!("Transaction not included: {:?}", status).into()),
+			}
+		}
+		Err("The node stopped reporting the status of the transaction".into())
+	}
+}
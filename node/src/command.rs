@@ -16,8 +16,8 @@
 // limitations under the License.
 
 use crate::{chain_spec, service};
-use crate::cli::{Cli, Subcommand};
-use sc_cli::{SubstrateCli, RuntimeVersion, Role, ChainSpec};
+use crate::cli::{Cli, LogFormat, Subcommand};
+use sc_cli::{CliConfiguration, SubstrateCli, RuntimeVersion, Role, ChainSpec, Runner};
 use sc_service::PartialComponents;
 use node_template_runtime::Block;
 
@@ -59,11 +59,55 @@ impl SubstrateCli for Cli {
 	fn native_runtime_version(_: &Box<dyn ChainSpec>) -> &'static RuntimeVersion {
 		&node_template_runtime::VERSION
 	}
+
+	fn create_runner<T: CliConfiguration>(&self, command: &T) -> sc_cli::Result<Runner<Self>> {
+		match self.log_format {
+			LogFormat::Text => command.init::<Self>()?,
+			LogFormat::Json => {
+				// What `CliConfiguration::init` does, but with the JSON logger, which neither
+				// profiles nor reloads its filters.
+				reject_unsupported_by_json_logger(command)?;
+				sp_panic_handler::set(&Self::support_url(), &Self::impl_version());
+				crate::logging::init_json_logger(&command.log_filters()?)?;
+				if let Some(limit) = fdlimit::raise_fd_limit() {
+					if limit < RECOMMENDED_OPEN_FILE_DESCRIPTOR_LIMIT {
+						log::warn!(
+							"Low open file descriptor limit configured for the process. \
+							Current value: {:?}, recommended value: {:?}.",
+							limit,
+							RECOMMENDED_OPEN_FILE_DESCRIPTOR_LIMIT,
+						);
+					}
+				}
+			},
+		}
+		Runner::new(self, command)
+	}
+}
+
+/// The open file descriptor limit below which a warning is logged, as Substrate does.
+const RECOMMENDED_OPEN_FILE_DESCRIPTOR_LIMIT: u64 = 10_000;
+
+/// Fail on the options of `command` the JSON logger would silently ignore.
+fn reject_unsupported_by_json_logger<T: CliConfiguration>(command: &T) -> sc_cli::Result<()> {
+	if command.tracing_targets()?.is_some() {
+		return Err(
+			"`--tracing-targets` and `--tracing-receiver` are not supported with \
+			`--log-format json`".into()
+		)
+	}
+	if command.enable_log_reloading()? {
+		return Err("`--enable-log-reloading` is not supported with `--log-format json`".into())
+	}
+	Ok(())
 }
 
 /// Parse and run command line arguments
 pub fn run() -> sc_cli::Result<()> {
-	let cli = Cli::from_args();
+	let mut cli = Cli::from_args();
+	if let Some(profile) = cli.profile {
+		profile.apply(&mut cli.run);
+	}
 
 	match &cli.subcommand {
 		Some(Subcommand::Key(cmd)) => cmd.run(&cli),
@@ -115,6 +159,31 @@ pub fn run() -> sc_cli::Result<()> {
 				Ok((cmd.run(client, backend), task_manager))
 			})
 		},
+		Some(Subcommand::Inspect(cmd)) => {
+			let runner = cli.create_runner(cmd)?;
+			runner.sync_run(|config| cmd.run(config))
+		},
+		Some(Subcommand::Tx(cmd)) => {
+			let runner = cli.create_runner(cmd)?;
+			// Transactions are submitted to a running node, no service is started here.
+			runner.sync_run(|_| cmd.run())
+		},
+		Some(Subcommand::DbStats(cmd)) => {
+			let runner = cli.create_runner(cmd)?;
+			runner.sync_run(|config| cmd.run(config))
+		},
+		Some(Subcommand::ForkOff(cmd)) => {
+			let runner = cli.create_runner(cmd)?;
+			runner.sync_run(|config| cmd.run(config.chain_spec))
+		},
+		Some(Subcommand::IndexEvents(cmd)) => {
+			let runner = cli.create_runner(cmd)?;
+			runner.async_run(|config| {
+				let PartialComponents { client, task_manager, ..}
+					= service::new_partial(&config)?;
+				Ok((cmd.run(client), task_manager))
+			})
+		},
 		Some(Subcommand::Benchmark(cmd)) => {
 			if cfg!(feature = "runtime-benchmarks") {
 				let runner = cli.create_runner(cmd)?;
@@ -127,10 +196,13 @@ pub fn run() -> sc_cli::Result<()> {
 		},
 		None => {
 			let runner = cli.create_runner(&cli.run)?;
-			runner.run_node_until_exit(|config| async move {
+			let pool = cli.pool.clone();
+			let health = cli.health.config();
+			runner.run_node_until_exit(|mut config| async move {
+				pool.apply(&mut config.transaction_pool);
 				match config.role {
-					Role::Light => service::new_light(config),
-					_ => service::new_full(config),
+					Role::Light => service::new_light(config, health),
+					_ => service::new_full(config, health),
 				}.map_err(sc_cli::Error::Service)
 			})
 		}
@@ -0,0 +1,117 @@
+//! Monitoring of the number of blocks the best block is ahead of the last finalized one, and
+//! verification of the GRANDPA finality proofs served by `grandpa_proveFinality`.
+
+use std::sync::Arc;
+
+use codec::Decode;
+use finality_grandpa::voter_set::VoterSet;
+use futures::{stream, StreamExt};
+use node_template_runtime::{opaque::Block, BlockNumber, Hash, Header};
+use sc_client_api::BlockchainEvents;
+use sc_finality_grandpa::{FinalityProof, GrandpaJustification};
+use sp_blockchain::HeaderBackend;
+use sp_finality_grandpa::{AuthorityList, Commit};
+use substrate_prometheus_endpoint::{register, Gauge, PrometheusError, Registry, U64};
+
+/// The finality lag, in blocks, above which warnings are logged.
+pub const FINALITY_LAG_WARNING: BlockNumber = 32;
+
+/// Tracks the finality lag, warning when it exceeds a threshold.
+pub struct LagMonitor {
+	threshold: BlockNumber,
+	warned_at: Option<BlockNumber>,
+	gauge: Option<Gauge<U64>>,
+}
+
+impl LagMonitor {
+	/// A monitor warning above `threshold`, and registering a gauge in `registry` if any.
+	pub fn new(threshold: BlockNumber, registry: Option<&Registry>) -> Result<Self, PrometheusError> {
+		let gauge = registry.map(|registry| register(
+			Gauge::new(
+				"grandpa_finality_lag_blocks",
+				"Number of blocks the best block is ahead of the last finalized block",
+			)?,
+			registry,
+		)).transpose()?;
+		Ok(Self { threshold, warned_at: None, gauge })
+	}
+
+	/// Record the best and the last finalized block numbers, returning whether a warning was
+	/// logged.
+	///
+	/// Warns when the lag exceeds the threshold, then each time it grows by the threshold again,
+	/// until finality catches up.
+	pub fn record(&mut self, best: BlockNumber, finalized: BlockNumber) -> bool {
+		let lag = best.saturating_sub(finalized);
+		if let Some(gauge) = &self.gauge {
+			gauge.set(lag.into());
+		}
+
+		if lag <= self.threshold {
+			if self.warned_at.take().is_some() {
+				log::info!(target: "afg", "Finality caught up, {} blocks behind the best block", lag);
+			}
+			return false
+		}
+		match self.warned_at {
+			Some(warned_at) if lag < warned_at.saturating_add(self.threshold) => false,
+			_ => {
+				log::warn!(
+					target: "afg",
+					"Finality is lagging: #{} finalized, {} blocks behind the best block #{}",
+					finalized,
+					lag,
+					best,
+				);
+				self.warned_at = Some(lag);
+				true
+			},
+		}
+	}
+}
+
+/// Update `monitor` whenever `client` imports or finalizes a block.
+pub async fn monitor_finality_lag<C>(client: Arc<C>, mut monitor: LagMonitor) where
+	C: BlockchainEvents<Block> + HeaderBackend<Block>,
+{
+	let mut events = stream::select(
+		client.import_notification_stream().map(|_| ()),
+		client.finality_notification_stream().map(|_| ()),
+	);
+	while events.next().await.is_some() {
+		let info = client.info();
+		monitor.record(info.best_number, info.finalized_number);
+	}
+}
+
+/// Verify a finality proof returned by `grandpa_proveFinality`, as a light client or a bridge
+/// following the authority set `set_id` of `authorities` would.
+///
+/// The justification of the proof is verified by `sc_finality_grandpa`, which also rejects
+/// ancestries no precommit needs. Returns the hash and the number of the block the proof
+/// finalizes.
+pub fn verify_finality_proof(
+	proof: &[u8],
+	set_id: u64,
+	authorities: &AuthorityList,
+) -> Result<(Hash, BlockNumber), String> {
+	let proof = FinalityProof::<Header>::decode(&mut &proof[..])
+		.map_err(|e| format!("Invalid finality proof: {}", e))?;
+	// The justification starts with its round and commit, whose target the proof only has the
+	// hash of.
+	let (_round, commit) = <(u64, Commit<Header>)>::decode(&mut &proof.justification[..])
+		.map_err(|e| format!("Invalid justification: {}", e))?;
+	if commit.target_hash != proof.block {
+		return Err("The justification is for another block than the proof".into())
+	}
+
+	let voters = VoterSet::new(authorities.iter().cloned())
+		.ok_or_else(|| format!("Invalid authority set {}", set_id))?;
+	GrandpaJustification::<Block>::decode_and_verify_finalizes(
+		&proof.justification,
+		(commit.target_hash, commit.target_number),
+		set_id,
+		&voters,
+	).map_err(|e| format!("Invalid justification: {}", e))?;
+	Ok((commit.target_hash, commit.target_number))
+}
@@ -0,0 +1,173 @@
+//! An HTTP endpoint reporting the health of the node, for liveness and readiness probes.
+//!
+//! `GET /health` answers `200` when the node has enough peers and, once synced, finality keeps up
+//! and the local authority, if any, authors blocks in its slots. `GET /ready` answers `200` when
+//! the node has enough peers and is not syncing. Both answer `503` otherwise, with a JSON report
+//! of the state of the node and of the problems found.
+
+use std::{convert::Infallible, net::SocketAddr, sync::Arc};
+
+use hyper::{
+	server::{conn::AddrIncoming, Builder},
+	service::{make_service_fn, service_fn},
+	Body, Method, Request, Response, Server, StatusCode,
+};
+use node_template_runtime::BlockNumber;
+use serde::Serialize;
+
+/// Where the endpoint listens and when the node is unhealthy.
+#[derive(Debug, Clone)]
+pub struct HealthConfig {
+	/// The address the endpoint listens on.
+	pub address: SocketAddr,
+	/// The thresholds beyond which the node is unhealthy.
+	pub thresholds: HealthThresholds,
+}
+
+/// The thresholds beyond which the node is unhealthy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HealthThresholds {
+	/// The least number of peers a healthy node is connected to.
+	pub min_peers: usize,
+	/// The most blocks a healthy node's best block is ahead of its last finalized block.
+	pub max_finality_lag: BlockNumber,
+	/// The most consecutive slots a healthy local authority authors no block in.
+	pub max_missed_slots: u64,
+}
+
+/// The state of the node, as reported by the endpoint.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthStatus {
+	/// The number of connected peers.
+	pub peers: usize,
+	/// Whether the node is syncing a large number of blocks.
+	pub is_syncing: bool,
+	/// The number of the best block.
+	pub best_number: BlockNumber,
+	/// The number of the last finalized block.
+	pub finalized_number: BlockNumber,
+	/// The consecutive slots the local authority authored no block in, up to the slot of the wall
+	/// clock, `null` for nodes which are not authorities.
+	pub missed_slots: Option<u64>,
+}
+
+impl HealthStatus {
+	/// Why the node is not healthy, if it isn't.
+	///
+	/// Finality and missed slots are only checked once the node is synced, as a syncing node
+	/// imports justifications far apart and doesn't author blocks.
+	pub fn health_problems(&self, thresholds: &HealthThresholds) -> Vec<String> {
+		let mut problems = Vec::new();
+		if self.peers < thresholds.min_peers {
+			problems.push(self.peers_problem(thresholds));
+		}
+		if self.is_syncing {
+			return problems
+		}
+
+		let lag = self.best_number.saturating_sub(self.finalized_number);
+		if lag > thresholds.max_finality_lag {
+			problems.push(format!(
+				"finality lags {} blocks behind, more than {}",
+				lag, thresholds.max_finality_lag,
+			));
+		}
+		match self.missed_slots {
+			Some(missed) if missed > thresholds.max_missed_slots => problems.push(format!(
+				"the local authority missed {} consecutive slots, more than {}",
+				missed, thresholds.max_missed_slots,
+			)),
+			_ => (),
+		}
+		problems
+	}
+
+	/// Why the node is not ready to serve requests, if it isn't.
+	pub fn readiness_problems(&self, thresholds: &HealthThresholds) -> Vec<String> {
+		let mut problems = Vec::new();
+		if self.peers < thresholds.min_peers {
+			problems.push(self.peers_problem(thresholds));
+		}
+		if self.is_syncing {
+			problems.push("the node is syncing".into());
+		}
+		problems
+	}
+
+	fn peers_problem(&self, thresholds: &HealthThresholds) -> String {
+		format!("{} peers connected, fewer than {}", self.peers, thresholds.min_peers)
+	}
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Report<'a> {
+	healthy: bool,
+	problems: &'a [String],
+	#[serde(flatten)]
+	status: &'a HealthStatus,
+}
+
+fn respond(status: &HealthStatus, problems: &[String]) -> Response<Body> {
+	let report = Report { healthy: problems.is_empty(), problems, status };
+	let code = if problems.is_empty() { StatusCode::OK } else { StatusCode::SERVICE_UNAVAILABLE };
+	Response::builder()
+		.status(code)
+		.header("Content-Type", "application/json")
+		.body(Body::from(serde_json::to_vec(&report).expect("the report serializes; qed")))
+		.expect("the response is valid; qed")
+}
+
+/// Answer a request to the endpoint, reading the state of the node with `status`.
+pub fn handle(
+	request: &Request<Body>,
+	status: impl Fn() -> HealthStatus,
+	thresholds: &HealthThresholds,
+) -> Response<Body> {
+	if request.method() != Method::GET {
+		return Response::builder()
+			.status(StatusCode::METHOD_NOT_ALLOWED)
+			.body(Body::empty())
+			.expect("the response is valid; qed")
+	}
+	match request.uri().path() {
+		"/health" => {
+			let status = status();
+			respond(&status, &status.health_problems(thresholds))
+		},
+		"/ready" => {
+			let status = status();
+			respond(&status, &status.readiness_problems(thresholds))
+		},
+		_ => Response::builder()
+			.status(StatusCode::NOT_FOUND)
+			.body(Body::empty())
+			.expect("the response is valid; qed"),
+	}
+}
+
+/// Bind the endpoint to `address`, failing if the address is in use.
+pub fn bind(address: &SocketAddr) -> Result<Builder<AddrIncoming>, hyper::Error> {
+	Server::try_bind(address)
+}
+
+/// Serve the endpoint on `server`, reading the state of the node with `status`.
+pub async fn serve<S>(server: Builder<AddrIncoming>, status: S, thresholds: HealthThresholds) where
+	S: Fn() -> HealthStatus + Send + Sync + 'static,
+{
+	let status = Arc::new(status);
+	let make_service = make_service_fn(move |_| {
+		let status = status.clone();
+		async move {
+			Ok::<_, Infallible>(service_fn(move |request| {
+				let response = handle(&request, &*status, &thresholds);
+				async move { Ok::<_, Infallible>(response) }
+			}))
+		}
+	});
+
+	if let Err(e) = server.serve(make_service).await {
+		log::error!(target: "health", "Health endpoint failed: {}", e);
+	}
+}
@@ -1,3 +1,11 @@
 pub mod chain_spec;
+pub mod cli;
+pub mod cmd;
+pub mod command;
+pub mod finality;
+pub mod health;
+pub mod logging;
 pub mod service;
+pub mod pool;
 pub mod rpc;
+pub mod slots;
@@ -0,0 +1,191 @@
+//! Logging as JSON Lines, for log pipelines to filter on.
+//!
+//! Each record is an object with the `timestamp`, `level`, `target` and `message` of the event and
+//! the fields it recorded. Most of the node logs through `log`, which only records a message: the
+//! first block number, block hash and peer id found in the message are added as `blockNumber`,
+//! `blockHash` and `peerId`. These are best effort, as they are matched in free text: a `#42` that
+//! isn't a block number is taken for one, and abbreviated hashes are kept as they were displayed.
+//! Logs of the runtime are forwarded from Wasm with their `runtime::*` targets.
+//!
+//! The JSON logger doesn't profile with `--tracing-targets` or reload its filters, and the node
+//! fails to start when asked to.
+
+use std::fmt;
+
+use chrono::{SecondsFormat, Utc};
+use regex::Regex;
+use serde_json::{Map, Value};
+use tracing::{
+	field::{Field, Visit},
+	level_filters::LevelFilter,
+	Event, Level, Subscriber,
+};
+use tracing_log::NormalizeEvent;
+use tracing_subscriber::{
+	filter::{Directive, EnvFilter},
+	fmt::{FmtContext, FormatEvent, FormatFields},
+	layer::Layer,
+	registry::LookupSpan,
+};
+
+/// The directives applied before `RUST_LOG` and `--log`, as the text logger of the node does.
+const DEFAULT_DIRECTIVES: &[&str] = &[
+	"info",
+	"ws=off",
+	"yamux=off",
+	"cranelift_codegen=off",
+	"cranelift_wasm=warn",
+	"hyper=warn",
+];
+
+/// Formats events as JSON objects, one per line.
+pub struct JsonFormat {
+	block_number: Regex,
+	block_hash: Regex,
+	peer_id: Regex,
+}
+
+impl JsonFormat {
+	/// A new format.
+	pub fn new() -> Self {
+		Self {
+			block_number: Regex::new(r"#(\d+)").expect("the regex is valid; qed"),
+			// Hashes are displayed in full or abbreviated, as in `0x1234…cdef`.
+			block_hash: Regex::new(r"0x[0-9a-fA-F]{4}(?:[0-9a-fA-F]{60}|…[0-9a-fA-F]{4})\b")
+				.expect("the regex is valid; qed"),
+			peer_id: Regex::new(r"\b(?:12D3KooW|Qm)[1-9A-HJ-NP-Za-km-z]{44}\b")
+				.expect("the regex is valid; qed"),
+		}
+	}
+
+	/// The record of an event at `level` for `target`, with the `fields` it recorded.
+	pub fn record(&self, level: &Level, target: &str, mut fields: Map<String, Value>) -> Value {
+		let message = match fields.remove("message") {
+			Some(Value::String(message)) => message,
+			Some(message) => message.to_string(),
+			None => String::new(),
+		};
+
+		let mut record = Map::new();
+		let timestamp = Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true);
+		record.insert("timestamp".into(), timestamp.into());
+		record.insert("level".into(), level.to_string().into());
+		record.insert("target".into(), target.into());
+
+		if let Some(number) = self.block_number.captures(&message)
+			.and_then(|captures| captures[1].parse::<u64>().ok())
+		{
+			fields.entry("blockNumber").or_insert_with(|| number.into());
+		}
+		if let Some(hash) = self.block_hash.find(&message) {
+			fields.entry("blockHash").or_insert_with(|| hash.as_str().into());
+		}
+		if let Some(peer_id) = self.peer_id.find(&message) {
+			fields.entry("peerId").or_insert_with(|| peer_id.as_str().into());
+		}
+
+		record.insert("message".into(), message.into());
+		record.extend(fields);
+		Value::Object(record)
+	}
+}
+
+impl Default for JsonFormat {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+/// The fields of an event, without the metadata `log` records are converted with.
+#[derive(Default)]
+struct Fields(Map<String, Value>);
+
+impl Fields {
+	fn insert(&mut self, field: &Field, value: Value) {
+		if !field.name().starts_with("log.") {
+			self.0.insert(field.name().into(), value);
+		}
+	}
+}
+
+impl Visit for Fields {
+	fn record_i64(&mut self, field: &Field, value: i64) {
+		self.insert(field, value.into());
+	}
+
+	fn record_u64(&mut self, field: &Field, value: u64) {
+		self.insert(field, value.into());
+	}
+
+	fn record_bool(&mut self, field: &Field, value: bool) {
+		self.insert(field, value.into());
+	}
+
+	fn record_str(&mut self, field: &Field, value: &str) {
+		self.insert(field, value.into());
+	}
+
+	fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+		self.insert(field, format!("{:?}", value).into());
+	}
+}
+
+impl<S, N> FormatEvent<S, N> for JsonFormat where
+	S: Subscriber + for<'a> LookupSpan<'a>,
+	N: for<'a> FormatFields<'a> + 'static,
+{
+	fn format_event(
+		&self,
+		_ctx: &FmtContext<'_, S, N>,
+		writer: &mut dyn fmt::Write,
+		event: &Event<'_>,
+	) -> fmt::Result {
+		// Events converted from `log` records carry the target of the record in their fields.
+		let normalized = event.normalized_metadata();
+		let metadata = normalized.as_ref().unwrap_or_else(|| event.metadata());
+
+		let mut fields = Fields::default();
+		event.record(&mut fields);
+		let record = self.record(metadata.level(), metadata.target(), fields.0);
+		writeln!(writer, "{}", record)
+	}
+}
+
+/// Log JSON records to stderr, filtered by the default directives, `RUST_LOG` and then
+/// `directives`.
+pub fn init_json_logger(directives: &str) -> Result<(), String> {
+	let env_directives = std::env::var("RUST_LOG").unwrap_or_default();
+	let mut filter = EnvFilter::default();
+	let all_directives = DEFAULT_DIRECTIVES.iter().copied()
+		.chain(env_directives.split(','))
+		.chain(directives.split(','))
+		.map(str::trim)
+		.filter(|directive| !directive.is_empty());
+	for directive in all_directives {
+		let directive = directive.parse::<Directive>()
+			.map_err(|e| format!("Invalid log directive {:?}: {}", directive, e))?;
+		filter = filter.add_directive(directive);
+	}
+
+	// Only the records the filter may keep are converted from `log`, and forwarded from Wasm.
+	let max_level = match Layer::<tracing_subscriber::Registry>::max_level_hint(&filter) {
+		Some(LevelFilter::OFF) => log::LevelFilter::Off,
+		Some(LevelFilter::ERROR) => log::LevelFilter::Error,
+		Some(LevelFilter::WARN) => log::LevelFilter::Warn,
+		Some(LevelFilter::INFO) => log::LevelFilter::Info,
+		Some(LevelFilter::DEBUG) => log::LevelFilter::Debug,
+		_ => log::LevelFilter::Trace,
+	};
+	tracing_log::LogTracer::builder()
+		.with_max_level(max_level)
+		.init()
+		.map_err(|e| format!("Unable to forward log records: {}", e))?;
+
+	let subscriber = tracing_subscriber::fmt()
+		.with_env_filter(filter)
+		.with_writer(std::io::stderr)
+		.event_format(JsonFormat::new())
+		.finish();
+	tracing::subscriber::set_global_default(subscriber)
+		.map_err(|e| format!("Unable to set the JSON logger: {}", e))
+}
@@ -1,12 +1,7 @@
 //! Substrate Node Template CLI library.
 #![warn(missing_docs)]
 
-mod chain_spec;
-#[macro_use]
-mod service;
-mod cli;
-mod command;
-mod rpc;
+use node_template::*;
 
 fn main() -> sc_cli::Result<()> {
 	command::run()
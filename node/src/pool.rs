@@ -0,0 +1,67 @@
+//! Priority based eviction of transactions from the transaction pool.
+//!
+//! Once full, the pool drops the oldest ready transactions to make room for new ones. Evicting
+//! the lowest priority transactions before that limit is reached makes sure the transactions
+//! paying the least per weight are the ones to go.
+
+use std::sync::Arc;
+
+use futures::StreamExt;
+use sc_transaction_graph::{error, ChainApi, ExtrinsicHash, Pool, ValidatedTransaction};
+use sp_transaction_pool::InPoolTransaction;
+
+/// The share of the ready queue limit, in percent, above which transactions are evicted.
+pub const EVICTION_THRESHOLD: usize = 90;
+
+/// The number of ready transactions kept in a pool holding at most `ready_limit` of them.
+pub fn eviction_threshold(ready_limit: usize) -> usize {
+	ready_limit.saturating_mul(EVICTION_THRESHOLD) / 100
+}
+
+/// Remove the lowest priority ready transactions until at most `max_ready` are left.
+///
+/// The transactions evicted are the ones block authors would include last: those of lowest
+/// priority, along with the transactions depending on them. They are not banned, and can be
+/// submitted again once the pool has room. Returns the hashes of the evicted transactions.
+pub fn evict_lowest_priority<A: ChainApi>(
+	pool: &Pool<A>,
+	max_ready: usize,
+) -> Vec<ExtrinsicHash<A>> {
+	// Ready transactions are yielded in the order they are included in blocks: by priority,
+	// each after the transactions it depends on.
+	let ready = pool.validated_pool().ready().map(|tx| tx.hash().clone()).collect::<Vec<_>>();
+	if ready.len() <= max_ready {
+		return Vec::new()
+	}
+	let evicted = ready[max_ready..].to_vec();
+
+	// Unlike `remove_invalid`, resubmitting transactions which failed validation removes them
+	// without banning them.
+	pool.resubmit(evicted.iter()
+		.map(|hash| {
+			let dropped = ValidatedTransaction::Unknown(
+				hash.clone(),
+				error::Error::ImmediatelyDropped.into(),
+			);
+			(hash.clone(), dropped)
+		})
+		.collect());
+
+	log::debug!(
+		target: "txpool",
+		"Evicted {} lowest priority transactions, {} ready left",
+		evicted.len(),
+		pool.validated_pool().status().ready,
+	);
+
+	evicted
+}
+
+/// Evict the lowest priority transactions whenever a transaction is imported into a pool
+/// holding more than `max_ready` ready transactions.
+pub async fn evict_by_priority<A: ChainApi>(pool: Arc<Pool<A>>, max_ready: usize) {
+	let mut imported = pool.validated_pool().import_notification_stream();
+	while imported.next().await.is_some() {
+		evict_lowest_priority(&*pool, max_ready);
+	}
+}
@@ -0,0 +1,129 @@
+//! RPC methods diagnosing why finality stalls.
+
+use std::{collections::HashSet, sync::Arc};
+
+use jsonrpc_core::Result;
+use jsonrpc_derive::rpc;
+use node_template_runtime::{opaque::Block, BlockNumber, Hash};
+use sc_finality_grandpa::{SharedAuthoritySet, SharedVoterState};
+use serde::{Deserialize, Serialize};
+use sp_blockchain::HeaderBackend;
+use sp_finality_grandpa::AuthorityId;
+
+/// GRANDPA diagnostics RPC methods.
+#[rpc]
+pub trait GrandpaDiagnosticsApi {
+	/// The state of the GRANDPA voter of this node and how far finality lags behind.
+	#[rpc(name = "grandpa_diagnostics")]
+	fn diagnostics(&self) -> Result<Diagnostics>;
+}
+
+/// The state of the GRANDPA voter and how far finality lags behind.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Diagnostics {
+	/// The id of the current authority set.
+	pub set_id: u64,
+	/// The number of the best block.
+	pub best_number: BlockNumber,
+	/// The number of the last finalized block.
+	pub finalized_number: BlockNumber,
+	/// The number of blocks the best block is ahead of the last finalized one.
+	pub lag: BlockNumber,
+	/// The best round of the voter, `null` until the voter has started.
+	pub round: Option<RoundDiagnostics>,
+}
+
+/// The votes of the best round of the voter.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RoundDiagnostics {
+	/// The round number.
+	pub round: u64,
+	/// The weight of all the authorities.
+	pub total_weight: u64,
+	/// The weight of the votes a prevote or a precommit needs to succeed.
+	pub threshold_weight: u64,
+	/// The weight of the prevotes so far.
+	pub prevote_weight: u64,
+	/// The weight of the precommits so far.
+	pub precommit_weight: u64,
+	/// Whether each authority of the current set voted in the round.
+	pub authorities: Vec<Participation>,
+}
+
+/// Whether an authority voted in a round.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Participation {
+	/// The authority, in SS58.
+	pub authority: String,
+	/// Whether the authority prevoted.
+	pub prevoted: bool,
+	/// Whether the authority precommitted.
+	pub precommitted: bool,
+}
+
+/// The participation of `authorities` in a round with `prevotes` and `precommits`.
+pub fn participation(
+	authorities: impl IntoIterator<Item = AuthorityId>,
+	prevotes: &HashSet<AuthorityId>,
+	precommits: &HashSet<AuthorityId>,
+) -> Vec<Participation> {
+	authorities.into_iter()
+		.map(|authority| Participation {
+			prevoted: prevotes.contains(&authority),
+			precommitted: precommits.contains(&authority),
+			authority: authority.to_string(),
+		})
+		.collect()
+}
+
+/// Implements the [`GrandpaDiagnosticsApi`] RPC trait from the state shared with the voter.
+pub struct GrandpaDiagnostics<C> {
+	client: Arc<C>,
+	shared_voter_state: SharedVoterState,
+	shared_authority_set: SharedAuthoritySet<Hash, BlockNumber>,
+}
+
+impl<C> GrandpaDiagnostics<C> {
+	/// Create a new `GrandpaDiagnostics` reading the state the voter shares.
+	pub fn new(
+		client: Arc<C>,
+		shared_voter_state: SharedVoterState,
+		shared_authority_set: SharedAuthoritySet<Hash, BlockNumber>,
+	) -> Self {
+		Self { client, shared_voter_state, shared_authority_set }
+	}
+}
+
+impl<C> GrandpaDiagnosticsApi for GrandpaDiagnostics<C> where
+	C: HeaderBackend<Block> + Send + Sync + 'static,
+{
+	fn diagnostics(&self) -> Result<Diagnostics> {
+		let info = self.client.info();
+		let round = self.shared_voter_state.voter_state().map(|voter_state| {
+			let (round, state) = voter_state.best_round;
+			let authorities = self.shared_authority_set.current_authorities()
+				.iter()
+				.map(|p| p.0.clone())
+				.collect::<Vec<_>>();
+			RoundDiagnostics {
+				round,
+				total_weight: state.total_weight.get(),
+				threshold_weight: state.threshold_weight.get(),
+				prevote_weight: state.prevote_current_weight.0,
+				precommit_weight: state.precommit_current_weight.0,
+				authorities: participation(authorities, &state.prevote_ids, &state.precommit_ids),
+			}
+		});
+
+		Ok(Diagnostics {
+			set_id: self.shared_authority_set.set_id(),
+			best_number: info.best_number,
+			finalized_number: info.finalized_number,
+			lag: info.best_number.saturating_sub(info.finalized_number),
+			round,
+		})
+	}
+}
@@ -0,0 +1,89 @@
+//! RPC methods resolving on-chain identities into human-readable names.
+
+use std::sync::Arc;
+
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use node_template_runtime::{apis::IdentityApi as IdentityRuntimeApi, opaque::Block, AccountId};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+
+/// The most accounts `identity_displayNames` resolves in one call.
+pub const MAX_ACCOUNTS: usize = 256;
+
+/// Identity RPC methods.
+#[rpc]
+pub trait IdentityApi<BlockHash> {
+	/// Resolve the display name of each of the given accounts.
+	///
+	/// Sub-accounts are reported as `parent/sub`. Accounts without an identity resolve to `null`.
+	/// At most [`MAX_ACCOUNTS`] accounts are resolved per call.
+	#[rpc(name = "identity_displayNames")]
+	fn display_names(
+		&self,
+		accounts: Vec<AccountId>,
+		at: Option<BlockHash>,
+	) -> Result<Vec<Option<String>>>;
+}
+
+/// Implements the [`IdentityApi`] RPC trait by calling the runtime.
+pub struct Identity<C> {
+	client: Arc<C>,
+}
+
+impl<C> Identity<C> {
+	/// Create a new `Identity` with the given reference to the client.
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client }
+	}
+}
+
+/// Error type of this RPC api.
+pub enum Error {
+	/// The call to the runtime failed.
+	RuntimeError,
+}
+
+impl From<Error> for i64 {
+	fn from(e: Error) -> i64 {
+		match e {
+			Error::RuntimeError => 1,
+		}
+	}
+}
+
+impl<C> IdentityApi<<Block as BlockT>::Hash> for Identity<C> where
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: IdentityRuntimeApi<Block, AccountId>,
+{
+	fn display_names(
+		&self,
+		accounts: Vec<AccountId>,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Vec<Option<String>>> {
+		if accounts.len() > MAX_ACCOUNTS {
+			return Err(RpcError::invalid_params(format!(
+				"{} accounts given, at most {} are resolved per call",
+				accounts.len(), MAX_ACCOUNTS,
+			)))
+		}
+
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash
+		));
+
+		accounts.into_iter()
+			.map(|account| {
+				let name = api.display_name(&at, account).map_err(|e| RpcError {
+					code: ErrorCode::ServerError(Error::RuntimeError.into()),
+					message: "Unable to resolve the display name.".into(),
+					data: Some(format!("{:?}", e).into()),
+				})?;
+				Ok(name.map(|name| String::from_utf8_lossy(&name).into_owned()))
+			})
+			.collect()
+	}
+}
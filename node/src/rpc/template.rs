@@ -0,0 +1,137 @@
+//! RPC methods reading the value stored by `pallet_template`, on full and light clients.
+
+use std::{marker::PhantomData, sync::Arc};
+
+use codec::Decode;
+use futures::{future::ready, FutureExt, TryFutureExt};
+use jsonrpc_core::{BoxFuture, Error as RpcError, ErrorCode};
+use jsonrpc_derive::rpc;
+use node_template_runtime::opaque::Block;
+use sc_client_api::{
+	light::{future_header, Fetcher, RemoteBlockchain, RemoteReadRequest},
+	Backend, StorageProvider,
+};
+use sp_blockchain::{Error as ClientError, HeaderBackend};
+use sp_core::{storage::StorageKey, twox_128};
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+
+/// Template RPC methods.
+#[rpc]
+pub trait TemplateApi<BlockHash> {
+	/// The value stored by `pallet_template::do_something`, `null` if none.
+	#[rpc(name = "template_something")]
+	fn something(&self, at: Option<BlockHash>) -> BoxFuture<Option<u32>>;
+}
+
+/// Storage key of `pallet_template::Something`.
+pub fn something_key() -> StorageKey {
+	StorageKey([twox_128(b"TemplateModule"), twox_128(b"Something")].concat())
+}
+
+fn decode_something(data: Option<&[u8]>) -> jsonrpc_core::Result<Option<u32>> {
+	data.map(|mut data| u32::decode(&mut data)).transpose().map_err(|e| RpcError {
+		code: ErrorCode::ServerError(Error::DecodeError.into()),
+		message: "Unable to decode the stored value.".into(),
+		data: Some(format!("{:?}", e).into()),
+	})
+}
+
+fn storage_error(e: ClientError) -> RpcError {
+	RpcError {
+		code: ErrorCode::ServerError(Error::StorageError.into()),
+		message: "Unable to read the stored value.".into(),
+		data: Some(format!("{:?}", e).into()),
+	}
+}
+
+/// Implements the [`TemplateApi`] RPC trait by reading the local storage of a full client.
+pub struct FullTemplate<C, B> {
+	client: Arc<C>,
+	_marker: PhantomData<B>,
+}
+
+impl<C, B> FullTemplate<C, B> {
+	/// Create a new `FullTemplate` with the given reference to the client.
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _marker: Default::default() }
+	}
+}
+
+impl<C, B> TemplateApi<<Block as BlockT>::Hash> for FullTemplate<C, B> where
+	B: Backend<Block> + Send + Sync + 'static,
+	C: StorageProvider<Block, B> + HeaderBackend<Block> + Send + Sync + 'static,
+{
+	fn something(&self, at: Option<<Block as BlockT>::Hash>) -> BoxFuture<Option<u32>> {
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash
+		));
+		let result = self.client.storage(&at, &something_key())
+			.map_err(storage_error)
+			.and_then(|data| decode_something(data.as_ref().map(|data| &data.0[..])));
+
+		Box::new(ready(result).boxed().compat())
+	}
+}
+
+/// Implements the [`TemplateApi`] RPC trait by fetching storage proofs from full nodes.
+pub struct LightTemplate<C, F> {
+	client: Arc<C>,
+	remote_blockchain: Arc<dyn RemoteBlockchain<Block>>,
+	fetcher: Arc<F>,
+}
+
+impl<C, F> LightTemplate<C, F> {
+	/// Create a new `LightTemplate` fetching storage with `fetcher`.
+	pub fn new(
+		client: Arc<C>,
+		remote_blockchain: Arc<dyn RemoteBlockchain<Block>>,
+		fetcher: Arc<F>,
+	) -> Self {
+		Self { client, remote_blockchain, fetcher }
+	}
+}
+
+impl<C, F> TemplateApi<<Block as BlockT>::Hash> for LightTemplate<C, F> where
+	C: HeaderBackend<Block> + Send + Sync + 'static,
+	F: Fetcher<Block> + 'static,
+{
+	fn something(&self, at: Option<<Block as BlockT>::Hash>) -> BoxFuture<Option<u32>> {
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+		let fetcher = self.fetcher.clone();
+		let key = something_key();
+
+		let header = future_header(&*self.remote_blockchain, &*self.fetcher, BlockId::Hash(at))
+			.and_then(move |header| ready(
+				header.ok_or_else(|| ClientError::UnknownBlock(format!("{}", at)))
+			));
+		let read = header.and_then(move |header| fetcher.remote_read(RemoteReadRequest {
+			block: at,
+			header,
+			keys: vec![key.0.clone()],
+			retry_count: None,
+		}).map_ok(move |mut values| values.remove(&key.0).flatten()));
+		let result = read
+			.map_err(storage_error)
+			.map(|value| value.and_then(|value| decode_something(value.as_deref())));
+
+		Box::new(result.boxed().compat())
+	}
+}
+
+/// Error type of this RPC api.
+pub enum Error {
+	/// The storage could not be read.
+	StorageError,
+	/// The storage entry could not be decoded.
+	DecodeError,
+}
+
+impl From<Error> for i64 {
+	fn from(e: Error) -> i64 {
+		match e {
+			Error::StorageError => 1,
+			Error::DecodeError => 2,
+		}
+	}
+}
@@ -1,6 +1,6 @@
 //! Service and ServiceFactory implementation. Specialized wrapper over substrate service.
 
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use sc_client_api::{ExecutorProvider, RemoteBackend};
 use node_template_runtime::{self, opaque::Block, RuntimeApi};
@@ -13,6 +13,9 @@ use sc_consensus_aura::{ImportQueueParams, StartAuraParams, SlotProportion};
 use sc_finality_grandpa::SharedVoterState;
 use sc_keystore::LocalKeystore;
 use sc_telemetry::{Telemetry, TelemetryWorker};
+use sp_blockchain::HeaderBackend;
+use sp_consensus::SyncOracle;
+use crate::health::{HealthConfig, HealthStatus};
 
 // Our native executor instance.
 native_executor_instance!(
@@ -125,8 +128,34 @@ fn remote_keystore(_url: &String) -> Result<Arc<LocalKeystore>, &'static str> {
 	Err("Remote Keystore not supported.")
 }
 
+/// Serve the health endpoint configured by `health`, if any, reading the state of the node with
+/// `status`.
+fn spawn_health_endpoint(
+	task_manager: &TaskManager,
+	health: Option<HealthConfig>,
+	status: impl Fn() -> HealthStatus + Send + Sync + 'static,
+) -> Result<(), ServiceError> {
+	let health = match health {
+		Some(health) => health,
+		None => return Ok(()),
+	};
+	let server = crate::health::bind(&health.address).map_err(|e| ServiceError::Other(
+		format!("Unable to bind the health endpoint to {}: {}", health.address, e)
+	))?;
+	log::info!(target: "health", "Health endpoint listening on http://{}", health.address);
+
+	task_manager.spawn_handle().spawn(
+		"health-endpoint",
+		crate::health::serve(server, status, health.thresholds),
+	);
+	Ok(())
+}
+
 /// Builds a new service for a full client.
-pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError> {
+pub fn new_full(
+	mut config: Configuration,
+	health: Option<HealthConfig>,
+) -> Result<TaskManager, ServiceError> {
 	let sc_service::PartialComponents {
 		client,
 		backend,
@@ -168,6 +197,14 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
 		);
 	}
 
+	task_manager.spawn_handle().spawn(
+		"txpool-eviction",
+		crate::pool::evict_by_priority(
+			transaction_pool.pool().clone(),
+			crate::pool::eviction_threshold(config.transaction_pool.ready.count),
+		),
+	);
+
 	let role = config.role.clone();
 	let force_authoring = config.force_authoring;
 	let backoff_authoring_blocks: Option<()> = None;
@@ -175,18 +212,34 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
 	let enable_grandpa = !config.disable_grandpa;
 	let prometheus_registry = config.prometheus_registry().cloned();
 
+	let shared_voter_state = SharedVoterState::empty();
+
 	let rpc_extensions_builder = {
 		let client = client.clone();
 		let pool = transaction_pool.clone();
+		let shared_voter_state = shared_voter_state.clone();
+		let shared_authority_set = grandpa_link.shared_authority_set().clone();
+		let justification_stream = grandpa_link.justification_stream();
+		let finality_proof_provider = sc_finality_grandpa::FinalityProofProvider::new_for_service(
+			backend.clone(),
+			Some(shared_authority_set.clone()),
+		);
 
-		Box::new(move |deny_unsafe, _| {
+		Box::new(move |deny_unsafe, subscription_executor| {
 			let deps = crate::rpc::FullDeps {
 				client: client.clone(),
 				pool: pool.clone(),
 				deny_unsafe,
+				grandpa: crate::rpc::GrandpaDeps {
+					shared_voter_state: shared_voter_state.clone(),
+					shared_authority_set: shared_authority_set.clone(),
+					justification_stream: justification_stream.clone(),
+					subscription_executor,
+					finality_provider: finality_proof_provider.clone(),
+				},
 			};
 
-			crate::rpc::create_full(deps)
+			crate::rpc::create_full::<_, _, FullBackend>(deps)
 		})
 	};
 
@@ -208,6 +261,51 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
 		},
 	)?;
 
+	let slot_metrics = prometheus_registry.as_ref()
+		.map(crate::slots::Metrics::register)
+		.transpose()
+		.map_err(ServiceError::Prometheus)?;
+	let slot_tracker = Arc::new(Mutex::new(crate::slots::SlotTracker::new(slot_metrics)));
+	task_manager.spawn_handle().spawn(
+		"aura-slots",
+		crate::slots::track_slots(
+			client.clone(),
+			keystore_container.sync_keystore(),
+			slot_tracker.clone(),
+		),
+	);
+
+	{
+		let client = client.clone();
+		let network = network.clone();
+		let keystore = keystore_container.sync_keystore();
+		let slot_duration = sc_consensus_aura::slot_duration(&*client)?.get();
+		let is_authority = role.is_authority();
+		spawn_health_endpoint(&task_manager, health, move || {
+			let info = client.info();
+			HealthStatus {
+				peers: network.num_connected(),
+				is_syncing: SyncOracle::is_major_syncing(&mut &*network),
+				best_number: info.best_number,
+				finalized_number: info.finalized_number,
+				missed_slots: if is_authority {
+					Some(crate::slots::local_missed_now(&slot_tracker, &keystore, slot_duration))
+				} else {
+					None
+				},
+			}
+		})?;
+	}
+
+	let lag_monitor = crate::finality::LagMonitor::new(
+		crate::finality::FINALITY_LAG_WARNING,
+		prometheus_registry.as_ref(),
+	).map_err(ServiceError::Prometheus)?;
+	task_manager.spawn_handle().spawn(
+		"finality-lag",
+		crate::finality::monitor_finality_lag(client.clone(), lag_monitor),
+	);
+
 	if role.is_authority() {
 		let proposer_factory = sc_basic_authorship::ProposerFactory::new(
 			task_manager.spawn_handle(),
@@ -275,7 +373,7 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
 			network,
 			voting_rule: sc_finality_grandpa::VotingRulesBuilder::default().build(),
 			prometheus_registry,
-			shared_voter_state: SharedVoterState::empty(),
+			shared_voter_state,
 			telemetry: telemetry.as_ref().map(|x| x.handle()),
 		};
 
@@ -292,7 +390,10 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
 }
 
 /// Builds a new service for a light client.
-pub fn new_light(mut config: Configuration) -> Result<TaskManager, ServiceError> {
+pub fn new_light(
+	mut config: Configuration,
+	health: Option<HealthConfig>,
+) -> Result<TaskManager, ServiceError> {
 	let telemetry = config.telemetry_endpoints.clone()
 		.filter(|x| !x.is_empty())
 		.map(|endpoints| -> Result<_, sc_telemetry::Error> {
@@ -370,12 +471,35 @@ pub fn new_light(mut config: Configuration) -> Result<TaskManager, ServiceError>
 		);
 	}
 
+	let light_deps = crate::rpc::LightDeps {
+		remote_blockchain: backend.remote_blockchain(),
+		fetcher: on_demand.clone(),
+		client: client.clone(),
+		pool: transaction_pool.clone(),
+	};
+	let rpc_extensions = crate::rpc::create_light(light_deps);
+
+	{
+		let client = client.clone();
+		let network = network.clone();
+		spawn_health_endpoint(&task_manager, health, move || {
+			let info = client.info();
+			HealthStatus {
+				peers: network.num_connected(),
+				is_syncing: SyncOracle::is_major_syncing(&mut &*network),
+				best_number: info.best_number,
+				finalized_number: info.finalized_number,
+				missed_slots: None,
+			}
+		})?;
+	}
+
 	sc_service::spawn_tasks(sc_service::SpawnTasksParams {
 		remote_blockchain: Some(backend.remote_blockchain()),
 		transaction_pool,
 		task_manager: &mut task_manager,
 		on_demand: Some(on_demand),
-		rpc_extensions_builder: Box::new(|_, _| ()),
+		rpc_extensions_builder: Box::new(sc_service::NoopRpcExtensionBuilder(rpc_extensions)),
 		config,
 		client,
 		keystore: keystore_container.sync_keystore(),
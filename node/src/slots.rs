@@ -0,0 +1,312 @@
+//! Tracking of the Aura slots assigned to each authority and of the blocks they authored.
+//!
+//! The slot of each new best block is read from its Aura pre-digest. The slots between it and the
+//! slot of its parent had no block, and count as missed by the authorities they were assigned
+//! to. Blocks of forks that later become the best chain are counted again.
+//!
+//! The slots after the best block, up to the slot of the wall clock, are counted as missed when
+//! the local authorities are checked, so that an authority is seen missing its slots even when the
+//! chain stalls and no block is imported.
+
+use std::{
+	collections::HashMap,
+	sync::{Arc, Mutex},
+	time::{SystemTime, UNIX_EPOCH},
+};
+
+use futures::StreamExt;
+use node_template_runtime::opaque::Block;
+use sc_client_api::BlockchainEvents;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_consensus_aura::{
+	digests::CompatibleDigestItem,
+	sr25519::{AuthorityId as AuraId, AuthoritySignature},
+	AuraApi,
+};
+use sp_core::crypto::key_types::AURA;
+use sp_keystore::{SyncCryptoStore, SyncCryptoStorePtr};
+use sp_runtime::{generic::BlockId, traits::Header as _};
+use substrate_prometheus_endpoint::{
+	register, CounterVec, GaugeVec, Opts, PrometheusError, Registry, U64,
+};
+
+/// The number of consecutive slots a local authority misses before a warning is logged.
+pub const MISSED_SLOTS_WARNING: u64 = 2;
+
+/// The slots assigned to an authority and the blocks it authored in them.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct AuthorityStats {
+	/// The number of slots assigned to the authority.
+	pub assigned: u64,
+	/// The number of blocks the authority authored.
+	pub authored: u64,
+}
+
+impl AuthorityStats {
+	/// The number of slots the authority authored no block in.
+	pub fn missed(&self) -> u64 {
+		self.assigned - self.authored
+	}
+}
+
+/// Prometheus metrics of the slots of each authority.
+#[derive(Clone)]
+pub struct Metrics {
+	assigned: CounterVec<U64>,
+	authored: CounterVec<U64>,
+	local_missed: GaugeVec<U64>,
+}
+
+impl Metrics {
+	/// Register the metrics in `registry`.
+	pub fn register(registry: &Registry) -> Result<Self, PrometheusError> {
+		Ok(Self {
+			assigned: register(
+				CounterVec::new(
+					Opts::new(
+						"aura_slots_assigned_total",
+						"Number of Aura slots assigned to an authority",
+					),
+					&["authority"],
+				)?,
+				registry,
+			)?,
+			authored: register(
+				CounterVec::new(
+					Opts::new(
+						"aura_blocks_authored_total",
+						"Number of blocks authored by an authority",
+					),
+					&["authority"],
+				)?,
+				registry,
+			)?,
+			local_missed: register(
+				GaugeVec::new(
+					Opts::new(
+						"aura_local_consecutive_missed_slots",
+						"Number of consecutive slots a local authority authored no block in",
+					),
+					&["authority"],
+				)?,
+				registry,
+			)?,
+		})
+	}
+}
+
+/// The slots of each authority, as seen from the best blocks.
+#[derive(Default)]
+pub struct SlotTracker {
+	stats: HashMap<AuraId, AuthorityStats>,
+	consecutive_missed: HashMap<AuraId, u64>,
+	last_missed: HashMap<AuraId, u64>,
+	best: Option<(u64, Vec<AuraId>)>,
+	metrics: Option<Metrics>,
+}
+
+impl SlotTracker {
+	/// A tracker updating `metrics`, if any.
+	pub fn new(metrics: Option<Metrics>) -> Self {
+		Self { metrics, ..Default::default() }
+	}
+
+	/// The slots and blocks of `authority` so far.
+	pub fn stats(&self, authority: &AuraId) -> AuthorityStats {
+		self.stats.get(authority).copied().unwrap_or_default()
+	}
+
+	/// The number of consecutive slots the local `authority` authored no block in.
+	pub fn consecutive_missed(&self, authority: &AuraId) -> u64 {
+		self.consecutive_missed.get(authority).copied().unwrap_or_default()
+	}
+
+	/// The last slot the local `authority` authored no block in, if any.
+	pub fn last_missed(&self, authority: &AuraId) -> Option<u64> {
+		self.last_missed.get(authority).copied()
+	}
+
+	/// The most consecutive slots any local authority authored no block in.
+	pub fn local_consecutive_missed(&self) -> u64 {
+		self.consecutive_missed.values().copied().max().unwrap_or_default()
+	}
+
+	/// The most consecutive slots any local authority authored no block in, as of `current_slot`.
+	///
+	/// The slots after the best block and before `current_slot` count as missed by the local
+	/// authorities they are assigned to, according to the authorities of the best block's parent.
+	pub fn local_consecutive_missed_at(
+		&self,
+		current_slot: u64,
+		is_local: impl Fn(&AuraId) -> bool,
+	) -> u64 {
+		let (best_slot, authorities) = match &self.best {
+			Some((best_slot, authorities)) => (*best_slot, authorities),
+			None => return self.local_consecutive_missed(),
+		};
+		let count = authorities.len() as u64;
+		// The slots after `best_slot` and before `current_slot` assigned to the authority at
+		// `index`.
+		let assigned = |index: u64| {
+			let before = |slot: u64| slot / count + (slot % count > index) as u64;
+			before(current_slot.max(best_slot + 1)) - before(best_slot + 1)
+		};
+
+		let mut missed = self.consecutive_missed.clone();
+		for (index, authority) in authorities.iter().enumerate() {
+			if is_local(authority) {
+				*missed.entry(authority.clone()).or_default() += assigned(index as u64);
+			}
+		}
+		missed.values().copied().max().unwrap_or_default()
+	}
+
+	/// Record a block authored in `slot`, following a block authored in `parent_slot`, if any.
+	///
+	/// `authorities` are the authorities of the parent block, and `is_local` tells the ones this
+	/// node has the keys of. Warns when a local authority misses `MISSED_SLOTS_WARNING` slots in
+	/// a row, or more.
+	pub fn record_block(
+		&mut self,
+		parent_slot: Option<u64>,
+		slot: u64,
+		authorities: &[AuraId],
+		is_local: impl Fn(&AuraId) -> bool,
+	) {
+		if authorities.is_empty() {
+			return
+		}
+		self.best = Some((slot, authorities.to_vec()));
+		let count = authorities.len() as u64;
+		let author_of = |slot: u64| &authorities[(slot % count) as usize];
+
+		// The slots of a run of `missed` empty slots assigned to each authority.
+		let missed = parent_slot.map_or(0, |parent| slot.saturating_sub(parent + 1));
+		let parent_slot = parent_slot.unwrap_or_default();
+		for offset in 0..missed.min(count) {
+			let slots = (missed - offset + count - 1) / count;
+			let first = parent_slot + 1 + offset;
+			let authority = author_of(first);
+			self.assign(authority, slots, false);
+			if is_local(authority) {
+				let last = first + (slots - 1) * count;
+				self.last_missed.insert(authority.clone(), last);
+				let consecutive = self.consecutive_missed.entry(authority.clone()).or_default();
+				*consecutive += slots;
+				if *consecutive >= MISSED_SLOTS_WARNING {
+					log::warn!(
+						target: "aura",
+						"Local authority {} missed {} consecutive slots, the last one #{}",
+						authority,
+						consecutive,
+						last,
+					);
+				}
+				let consecutive = *consecutive;
+				self.set_local_missed(authority, consecutive);
+			}
+		}
+
+		let author = author_of(slot);
+		self.assign(author, 1, true);
+		if is_local(author) {
+			self.consecutive_missed.insert(author.clone(), 0);
+			self.set_local_missed(author, 0);
+		}
+	}
+
+	fn assign(&mut self, authority: &AuraId, slots: u64, authored: bool) {
+		let stats = self.stats.entry(authority.clone()).or_default();
+		stats.assigned += slots;
+		if authored {
+			stats.authored += 1;
+		}
+
+		if let Some(metrics) = &self.metrics {
+			let label = authority.to_string();
+			metrics.assigned.with_label_values(&[&label]).inc_by(slots);
+			if authored {
+				metrics.authored.with_label_values(&[&label]).inc();
+			}
+		}
+	}
+
+	fn set_local_missed(&self, authority: &AuraId, consecutive: u64) {
+		if let Some(metrics) = &self.metrics {
+			metrics.local_missed.with_label_values(&[&authority.to_string()]).set(consecutive);
+		}
+	}
+}
+
+/// The slot of the Aura pre-digest of `header`, if any.
+fn slot_of(header: &<Block as sp_runtime::traits::Block>::Header) -> Option<u64> {
+	header.digest().logs().iter()
+		.find_map(CompatibleDigestItem::<AuthoritySignature>::as_aura_pre_digest)
+		.map(u64::from)
+}
+
+/// Whether an authority is one of the Aura keys in `keystore`.
+fn is_local_in(keystore: &SyncCryptoStorePtr) -> impl Fn(&AuraId) -> bool {
+	let local = SyncCryptoStore::sr25519_public_keys(&**keystore, AURA);
+	move |authority| local.iter().any(|key| AuraId::from(*key) == *authority)
+}
+
+/// The slot of the wall clock, for slots of `slot_duration` milliseconds.
+pub fn current_slot(slot_duration: u64) -> u64 {
+	let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+	now.as_millis() as u64 / slot_duration
+}
+
+/// The most consecutive slots an authority whose keys are in `keystore` authored no block in,
+/// up to the slot of the wall clock.
+pub fn local_missed_now(
+	tracker: &Mutex<SlotTracker>,
+	keystore: &SyncCryptoStorePtr,
+	slot_duration: u64,
+) -> u64 {
+	let tracker = tracker.lock().expect("the slot tracker doesn't panic; qed");
+	tracker.local_consecutive_missed_at(current_slot(slot_duration), is_local_in(keystore))
+}
+
+/// Track the slots of each authority on the best chain of `client` in `tracker`, warning when the
+/// authorities whose keys are in `keystore` miss slots.
+pub async fn track_slots<C>(
+	client: Arc<C>,
+	keystore: SyncCryptoStorePtr,
+	tracker: Arc<Mutex<SlotTracker>>,
+) where
+	C: BlockchainEvents<Block> + HeaderBackend<Block> + ProvideRuntimeApi<Block>,
+	C::Api: AuraApi<Block, AuraId>,
+{
+	let mut imported = client.import_notification_stream();
+	while let Some(notification) = imported.next().await {
+		if !notification.is_new_best {
+			continue
+		}
+		let slot = match slot_of(&notification.header) {
+			Some(slot) => slot,
+			None => continue,
+		};
+
+		let parent = *notification.header.parent_hash();
+		let parent_slot = match client.header(BlockId::Hash(parent)) {
+			Ok(header) => header.as_ref().and_then(slot_of),
+			Err(e) => {
+				log::debug!(target: "aura", "Missing header of block {:?}: {}", parent, e);
+				None
+			},
+		};
+		let authorities = match client.runtime_api().authorities(&BlockId::Hash(parent)) {
+			Ok(authorities) => authorities,
+			Err(e) => {
+				log::debug!(target: "aura", "No Aura authorities at block {:?}: {}", parent, e);
+				continue
+			},
+		};
+
+		tracker.lock()
+			.expect("the slot tracker doesn't panic; qed")
+			.record_block(parent_slot, slot, &authorities, is_local_in(&keystore));
+	}
+}
@@ -0,0 +1,50 @@
+use node_template::cli::{Cli, Profile};
+use sc_cli::{RpcMethods, RunCmd};
+use structopt::StructOpt;
+
+/// The options of `node-template` run with `args` and `profile`.
+fn run(profile: Profile, args: &[&str]) -> RunCmd {
+	let mut cli = Cli::from_iter(std::iter::once("node-template").chain(args.iter().copied()));
+	profile.apply(&mut cli.run);
+	cli.run
+}
+
+#[test]
+fn profiles_fill_the_options_left_to_their_defaults() {
+	let cmd = run(Profile::LightServer, &[]);
+	let import_params = &cmd.import_params;
+	assert_eq!(import_params.pruning_params.pruning.as_deref(), Some("4096"));
+	assert_eq!(import_params.database_params.database_cache_size, Some(512));
+	assert_eq!(import_params.state_cache_size, 256 * 1024 * 1024);
+	assert_eq!(cmd.ws_max_connections, Some(1000));
+
+	let cmd = run(Profile::Archive, &[
+		"--pruning", "1000",
+		"--db-cache", "64",
+		"--state-cache-size", "1024",
+		"--ws-max-connections", "5",
+		"--rpc-methods", "unsafe",
+	]);
+	let import_params = &cmd.import_params;
+	assert_eq!(import_params.pruning_params.pruning.as_deref(), Some("1000"));
+	assert_eq!(import_params.database_params.database_cache_size, Some(64));
+	assert_eq!(import_params.state_cache_size, 1024);
+	assert_eq!(cmd.ws_max_connections, Some(5));
+	assert_eq!(cmd.rpc_methods, RpcMethods::Unsafe);
+}
+
+#[test]
+fn profiles_leave_the_pruning_of_authorities_to_substrate() {
+	for args in &[&["--validator"][..], &["--dev"], &["--alice"]] {
+		let cmd = run(Profile::Full, args);
+		assert_eq!(cmd.import_params.pruning_params.pruning, None, "{:?}", args);
+		assert_eq!(cmd.import_params.database_params.database_cache_size, Some(128), "{:?}", args);
+	}
+}
+
+#[test]
+fn archive_and_light_server_profiles_only_expose_safe_rpc_methods() {
+	assert_eq!(run(Profile::Archive, &[]).rpc_methods, RpcMethods::Safe);
+	assert_eq!(run(Profile::LightServer, &[]).rpc_methods, RpcMethods::Safe);
+	assert_eq!(run(Profile::Full, &[]).rpc_methods, RpcMethods::Auto);
+}
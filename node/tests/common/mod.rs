@@ -0,0 +1,64 @@
+//! Helpers to run development nodes and talk to their RPC endpoints.
+
+use std::{
+	process::{Child, Command},
+	thread::sleep,
+	time::{Duration, Instant},
+};
+
+use futures::{compat::Future01CompatExt, Future, FutureExt, TryFutureExt};
+use jsonrpc_core_client::{transports::ws, RpcChannel};
+
+/// Kills the node when dropped, so failing tests don't leave it running.
+pub struct Running(Child);
+
+impl Drop for Running {
+	fn drop(&mut self) {
+		let _ = self.0.kill();
+		let _ = self.0.wait();
+	}
+}
+
+/// Start the node with `args`, without Prometheus, telemetry and mDNS.
+pub fn start(args: &[&str]) -> Running {
+	let child = Command::new(env!("CARGO_BIN_EXE_node-template"))
+		.args(&["--no-prometheus", "--no-telemetry", "--no-mdns"])
+		.args(args)
+		.spawn()
+		.unwrap();
+	Running(child)
+}
+
+/// The tokio 0.1 runtime the RPC clients and their connections run on.
+pub struct Runtime(tokio01::runtime::Runtime);
+
+impl Runtime {
+	pub fn new() -> Self {
+		Self(tokio01::runtime::Runtime::new().unwrap())
+	}
+
+	pub fn block_on<T>(&mut self, future: impl Future<Output = T> + Send + 'static) -> T where
+		T: Send + 'static,
+	{
+		self.0.block_on(future.map(Ok::<_, ()>).boxed().compat()).unwrap()
+	}
+
+	pub fn channel(&mut self, url: &'static str) -> RpcChannel {
+		eventually(Duration::from_secs(60), || {
+			let url = url::Url::parse(url).unwrap();
+			self.block_on(async move { ws::connect::<RpcChannel>(&url).compat().await.ok() })
+		})
+	}
+}
+
+/// Poll `check` every second until it returns `Some`, for at most `timeout`.
+pub fn eventually<T>(timeout: Duration, mut check: impl FnMut() -> Option<T>) -> T {
+	let start = Instant::now();
+	loop {
+		if let Some(value) = check() {
+			return value
+		}
+		assert!(start.elapsed() < timeout, "timed out");
+		sleep(Duration::from_secs(1));
+	}
+}
@@ -0,0 +1,77 @@
+use std::{future::Future, pin::Pin};
+
+use node_template::{
+	cli::Cli,
+	cmd::{state_usage, Usage},
+	service::new_partial,
+};
+use sc_cli::SubstrateCli;
+use sc_service::{PartialComponents, TaskExecutor, TaskType};
+use sp_core::twox_128;
+use sp_runtime::{generic::BlockId, BuildStorage};
+use structopt::StructOpt;
+
+type Task = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+fn item(pallet: &str, item: &str) -> Vec<u8> {
+	[twox_128(pallet.as_bytes()), twox_128(item.as_bytes())].concat()
+}
+
+/// The usage of the entries of `state` whose keys start with `prefix`.
+fn usage<'a>(state: impl IntoIterator<Item = (&'a Vec<u8>, &'a Vec<u8>)>, prefix: &[u8]) -> Usage {
+	let mut usage = Usage::default();
+	for (key, value) in state.into_iter().filter(|(key, _)| key.starts_with(prefix)) {
+		usage.add(key, value);
+	}
+	usage
+}
+
+#[test]
+fn state_is_counted_by_pallet_and_storage_item() {
+	let cli = Cli::from_iter(&["node-template", "--dev", "--tmp"]);
+	// The tasks of the client only need a thread each.
+	let task_executor = TaskExecutor::from(|task: Task, _: TaskType| {
+		std::thread::spawn(move || futures::executor::block_on(task));
+		async {}
+	});
+	let mut config = cli.create_configuration(&cli.run, task_executor).unwrap();
+
+	// A key of an item the runtime doesn't declare, and a key of no pallet.
+	let mut genesis = config.chain_spec.as_storage_builder().build_storage().unwrap();
+	let unknown_item = [&item("System", "Removed")[..], &[1, 2, 3]].concat();
+	genesis.top.insert(unknown_item.clone(), vec![0; 10]);
+	genesis.top.insert(b"no pallet".to_vec(), vec![0; 20]);
+	config.chain_spec.set_storage(genesis.clone());
+
+	let PartialComponents { client, .. } = new_partial(&config).unwrap();
+	let (pallets, items) = state_usage(&*client, &BlockId::Number(0)).unwrap();
+
+	let account = (String::from("System"), String::from("Account"));
+	assert_eq!(items[&account], usage(&genesis.top, &item("System", "Account")));
+	assert!(items[&account].entries > 0);
+	assert_eq!(pallets["Balances"], usage(&genesis.top, &twox_128(b"Balances")));
+
+	// Well known keys are counted each as an item of their own.
+	assert_eq!(pallets[":well_known"], usage(&genesis.top, b":"));
+	let code = (String::from(":well_known"), String::from(":code"));
+	assert_eq!(items[&code], usage(&genesis.top, b":code"));
+	assert_eq!(items[&code].value_bytes, genesis.top[&b":code".to_vec()].len() as u64);
+
+	let system_unknown = (String::from("System"), String::from("unknown"));
+	assert_eq!(items[&system_unknown], Usage { entries: 1, key_bytes: 35, value_bytes: 10 });
+	let unknown = (String::from("unknown"), String::from("unknown"));
+	assert_eq!(items[&unknown], Usage { entries: 1, key_bytes: 9, value_bytes: 20 });
+	assert_eq!(pallets["unknown"], items[&unknown]);
+
+	// Every entry is counted once.
+	let mut total = Usage::default();
+	for usage in pallets.values() {
+		total.add_all(usage);
+	}
+	assert_eq!(total, usage(&genesis.top, b""));
+	let mut total_of_items = Usage::default();
+	for usage in items.values() {
+		total_of_items.add_all(usage);
+	}
+	assert_eq!(total_of_items, total);
+}
@@ -0,0 +1,15 @@
+use node_template::finality::LagMonitor;
+
+#[test]
+fn warnings_repeat_as_the_lag_grows() {
+	let mut monitor = LagMonitor::new(10, None).unwrap();
+
+	assert!(!monitor.record(110, 100));
+	assert!(monitor.record(111, 100));
+	assert!(!monitor.record(120, 100));
+	assert!(monitor.record(121, 100));
+
+	// Finality catching up resets the warnings.
+	assert!(!monitor.record(122, 120));
+	assert!(monitor.record(140, 120));
+}
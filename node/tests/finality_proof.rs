@@ -0,0 +1,139 @@
+mod common;
+
+use std::time::Duration;
+
+use codec::Encode;
+use common::{eventually, start, Runtime};
+use finality_grandpa::{Commit, Message, Precommit, SignedPrecommit};
+use futures::compat::Future01CompatExt;
+use jsonrpc_core::Params;
+use jsonrpc_core_client::RawClient;
+use node_template::{chain_spec::authority_keys_from_seed, finality::verify_finality_proof};
+use node_template_runtime::{Hash, Header};
+use sc_finality_grandpa::FinalityProof;
+use sp_core::{Bytes, Pair};
+use sp_finality_grandpa::{localized_payload, AuthorityList, AuthorityPair};
+use sp_runtime::{traits::Header as _, Digest};
+
+const SET_ID: u64 = 3;
+const ROUND: u64 = 7;
+
+fn pairs() -> Vec<AuthorityPair> {
+	["//Alice", "//Bob", "//Charlie", "//Dave"].iter()
+		.map(|seed| AuthorityPair::from_string(seed, None).unwrap())
+		.collect()
+}
+
+fn authorities() -> AuthorityList {
+	pairs().iter().map(|pair| (pair.public(), 1)).collect()
+}
+
+fn header(number: u32, parent_hash: Hash) -> Header {
+	Header::new(number, Default::default(), Default::default(), parent_hash, Digest::default())
+}
+
+/// A proof finalizing `target`, precommitted by `signers` for `voted`.
+fn proof(target: &Header, voted: &Header, signers: &[AuthorityPair], set_id: u64) -> Vec<u8> {
+	let ancestries = if voted.hash() == target.hash() { vec![] } else { vec![voted.clone()] };
+	proof_with_ancestries(target, voted, signers, set_id, ancestries)
+}
+
+/// A proof finalizing `target`, precommitted by `signers` for `voted`, with `ancestries`.
+fn proof_with_ancestries(
+	target: &Header,
+	voted: &Header,
+	signers: &[AuthorityPair],
+	set_id: u64,
+	ancestries: Vec<Header>,
+) -> Vec<u8> {
+	let precommit = Precommit { target_hash: voted.hash(), target_number: *voted.number() };
+	let message = Message::<Hash, u32>::Precommit(precommit.clone());
+	let payload = localized_payload(ROUND, set_id, &message);
+	let commit = Commit {
+		target_hash: target.hash(),
+		target_number: *target.number(),
+		precommits: signers.iter()
+			.map(|pair| SignedPrecommit {
+				precommit: precommit.clone(),
+				signature: pair.sign(&payload),
+				id: pair.public(),
+			})
+			.collect(),
+	};
+
+	FinalityProof {
+		block: target.hash(),
+		justification: (ROUND, commit, ancestries).encode(),
+		unknown_headers: vec![],
+	}.encode()
+}
+
+fn verify(proof: &[u8]) -> Result<(Hash, u32), String> {
+	verify_finality_proof(proof, SET_ID, &authorities())
+}
+
+#[test]
+fn proofs_with_a_supermajority_verify() {
+	let target = header(10, Hash::repeat_byte(1));
+	let child = header(11, target.hash());
+	let pairs = pairs();
+
+	// Three of four authorities are enough.
+	assert_eq!(verify(&proof(&target, &target, &pairs[..3], SET_ID)), Ok((target.hash(), 10)));
+	// Precommits for a descendant count towards the target.
+	assert_eq!(verify(&proof(&target, &child, &pairs, SET_ID)), Ok((target.hash(), 10)));
+}
+
+#[test]
+fn proofs_without_a_supermajority_are_rejected() {
+	let target = header(10, Hash::repeat_byte(1));
+	let pairs = pairs();
+
+	assert!(verify(&proof(&target, &target, &pairs[..2], SET_ID)).is_err());
+	// Signatures are bound to the authority set.
+	assert!(verify(&proof(&target, &target, &pairs, SET_ID + 1)).is_err());
+	// Votes of authorities of other sets are rejected.
+	let stranger = AuthorityPair::from_string("//Eve", None).unwrap();
+	let signers = vec![pairs[0].clone(), pairs[1].clone(), stranger];
+	assert!(verify(&proof(&target, &target, &signers, SET_ID)).is_err());
+}
+
+#[test]
+fn proofs_with_unused_ancestries_are_rejected() {
+	let target = header(10, Hash::repeat_byte(1));
+	let unused = header(11, target.hash());
+
+	let proof = proof_with_ancestries(&target, &target, &pairs(), SET_ID, vec![unused]);
+	assert!(verify(&proof).is_err());
+}
+
+#[test]
+fn proofs_served_by_a_node_verify() {
+	let _node = start(&[
+		"--dev",
+		"--tmp",
+		"--rpc-methods", "unsafe",
+		"--port", "30436",
+		"--ws-port", "19947",
+	]);
+	let mut runtime = Runtime::new();
+	let client = RawClient::from(runtime.channel("ws://127.0.0.1:19947"));
+	let call = move |runtime: &mut Runtime, method: &'static str, block: u32| {
+		let client = client.clone();
+		runtime.block_on(async move {
+			client.call_method(method, Params::Array(vec![block.into()])).compat().await
+		})
+	};
+
+	// Block 1 is proven once it is finalized.
+	let proof = eventually(Duration::from_secs(120), || {
+		let proof = call(&mut runtime, "grandpa_proveFinality", 1).ok()?;
+		serde_json::from_value::<Option<Bytes>>(proof).ok()?
+	});
+	let alice = authority_keys_from_seed("Alice").1;
+	let (hash, number) = verify_finality_proof(&proof, 0, &vec![(alice, 1)]).unwrap();
+	assert!(number >= 1);
+
+	let block_hash = call(&mut runtime, "chain_getBlockHash", number).unwrap();
+	assert_eq!(serde_json::from_value::<Option<Hash>>(block_hash).unwrap(), Some(hash));
+}
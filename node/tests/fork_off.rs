@@ -0,0 +1,75 @@
+use codec::Encode;
+use node_template::{chain_spec, cmd::fork_off};
+use node_template_runtime::{AccountId, BlockNumber, BuildStorage};
+use pallet_escrow::EscrowDetails;
+use sp_core::{blake2_128, twox_128, twox_64};
+
+fn item(pallet: &str, item: &str) -> Vec<u8> {
+	[twox_128(pallet.as_bytes()), twox_128(item.as_bytes())].concat()
+}
+
+/// The key of `key` in the storage map `item` of `pallet`, hashed with `hash`.
+fn map_key(pallet: &str, name: &str, hash: &[u8], key: &[u8]) -> Vec<u8> {
+	[&item(pallet, name)[..], hash, key].concat()
+}
+
+#[test]
+fn forked_state_takes_the_keys_and_block_of_the_dev_chain() {
+	let dev = chain_spec::development_config().unwrap().build_storage().unwrap();
+	// Two authorities, which the dev chain spec replaces with one.
+	let mut forked = chain_spec::local_testnet_config().unwrap().build_storage().unwrap();
+	forked.top.insert(item("System", "Number"), 1_000u32.encode());
+	forked.top.insert(item("Aura", "CurrentSlot"), 4_242u64.encode());
+	forked.top.insert(item("TemplateModule", "Something"), 42u32.encode());
+	forked.top.insert(b":code".to_vec(), b"forked runtime".to_vec());
+
+	let state = fork_off(forked, &dev);
+
+	let authorities = item("Aura", "Authorities");
+	assert_eq!(state.top.get(&authorities), dev.top.get(&authorities));
+	let grandpa_authorities = &b":grandpa_authorities"[..];
+	assert_eq!(state.top.get(grandpa_authorities), dev.top.get(grandpa_authorities));
+	assert_eq!(state.top.get(&item("Sudo", "Key")), dev.top.get(&item("Sudo", "Key")));
+	assert_eq!(state.top.get(&item("System", "Number")), None);
+	assert_eq!(state.top.get(&item("Aura", "CurrentSlot")), None);
+
+	assert_eq!(state.top.get(&item("TemplateModule", "Something")), Some(&42u32.encode()));
+	assert_eq!(state.top.get(&b":code"[..]), Some(&b"forked runtime".to_vec()));
+}
+
+#[test]
+fn block_numbers_are_rebased_on_the_forked_block() {
+	let dev = chain_spec::development_config().unwrap().build_storage().unwrap();
+	let mut forked = chain_spec::local_testnet_config().unwrap().build_storage().unwrap();
+	forked.top.insert(item("System", "Number"), 1_000u32.encode());
+
+	let escrow = |expiry: BlockNumber| EscrowDetails {
+		payer: AccountId::from([1; 32]),
+		payee: AccountId::from([2; 32]),
+		arbiter: AccountId::from([3; 32]),
+		amount: 10u128,
+		expiry,
+	};
+	let expiring = |block: BlockNumber| {
+		map_key("Escrow", "Expiring", &twox_64(&block.encode()), &block.encode())
+	};
+	let escrows = map_key("Escrow", "Escrows", &twox_64(&0u32.encode()), &0u32.encode());
+	forked.top.insert(expiring(1_100), vec![0u32].encode());
+	forked.top.insert(escrows.clone(), escrow(1_100).encode());
+
+	let account = AccountId::from([1; 32]);
+	let last_free_call = map_key(
+		"TemplateModule",
+		"LastFreeCall",
+		&blake2_128(account.as_ref()),
+		account.as_ref(),
+	);
+	forked.top.insert(last_free_call.clone(), 990u32.encode());
+
+	let state = fork_off(forked, &dev);
+
+	assert_eq!(state.top.get(&expiring(1_100)), None);
+	assert_eq!(state.top.get(&expiring(100)), Some(&vec![0u32].encode()));
+	assert_eq!(state.top.get(&escrows), Some(&escrow(100).encode()));
+	assert_eq!(state.top.get(&last_free_call), Some(&0u32.encode()));
+}
@@ -0,0 +1,119 @@
+use std::{collections::{HashMap, HashSet}, future::Future, pin::Pin};
+
+use finality_grandpa::{
+	voter::{report, VoterState},
+	VoteWeight, VoterWeight,
+};
+use node_template::{
+	chain_spec::authority_keys_from_seed,
+	cli::Cli,
+	rpc::{create_full, grandpa::{participation, Participation}, DenyUnsafe, FullDeps, GrandpaDeps},
+	service::new_partial,
+};
+use sc_cli::SubstrateCli;
+use sc_finality_grandpa::{FinalityProofProvider, SharedVoterState};
+use sc_rpc::SubscriptionTaskExecutor;
+use sc_service::{PartialComponents, TaskExecutor, TaskType};
+use serde_json::{json, Value};
+use sp_finality_grandpa::AuthorityId;
+use structopt::StructOpt;
+
+#[test]
+fn participation_lists_every_authority() {
+	let (alice, bob) = (authority_keys_from_seed("Alice").1, authority_keys_from_seed("Bob").1);
+	let prevotes = vec![alice.clone(), bob.clone()].into_iter().collect::<HashSet<_>>();
+	let precommits = vec![alice.clone()].into_iter().collect::<HashSet<_>>();
+
+	assert_eq!(participation(vec![alice.clone(), bob.clone()], &prevotes, &precommits), vec![
+		Participation { authority: alice.to_string(), prevoted: true, precommitted: true },
+		Participation { authority: bob.to_string(), prevoted: true, precommitted: false },
+	]);
+}
+
+type Task = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// A voter in round 2 of the dev chain, which Alice prevoted in.
+struct Prevoted;
+
+impl VoterState<AuthorityId> for Prevoted {
+	fn get(&self) -> report::VoterState<AuthorityId> {
+		let alice = authority_keys_from_seed("Alice").1;
+		report::VoterState {
+			background_rounds: HashMap::new(),
+			best_round: (2, report::RoundState {
+				total_weight: VoterWeight::new(1).unwrap(),
+				threshold_weight: VoterWeight::new(1).unwrap(),
+				prevote_current_weight: VoteWeight(1),
+				prevote_ids: vec![alice].into_iter().collect(),
+				precommit_current_weight: VoteWeight(0),
+				precommit_ids: HashSet::new(),
+			}),
+		}
+	}
+}
+
+fn call(io: &jsonrpc_core::IoHandler<sc_rpc::Metadata>, method: &str) -> Value {
+	let request = json!({ "jsonrpc": "2.0", "method": method, "params": [], "id": 1 });
+	let response = io.handle_request_sync(&request.to_string(), Default::default()).unwrap();
+	let mut response = serde_json::from_str::<Value>(&response).unwrap();
+	response["result"].take()
+}
+
+#[test]
+fn diagnostics_are_served_from_the_shared_voter_state() {
+	let cli = Cli::from_iter(&["node-template", "--dev", "--tmp"]);
+	// The tasks of the client only need a thread each.
+	let task_executor = TaskExecutor::from(|task: Task, _: TaskType| {
+		std::thread::spawn(move || futures::executor::block_on(task));
+		async {}
+	});
+	let config = cli.create_configuration(&cli.run, task_executor).unwrap();
+	let PartialComponents {
+		client,
+		backend,
+		task_manager,
+		transaction_pool,
+		other: (_, grandpa_link, _),
+		..
+	} = new_partial(&config).unwrap();
+
+	let shared_voter_state = SharedVoterState::empty();
+	let shared_authority_set = grandpa_link.shared_authority_set().clone();
+	let io = create_full(FullDeps {
+		client,
+		pool: transaction_pool,
+		deny_unsafe: DenyUnsafe::No,
+		grandpa: GrandpaDeps {
+			shared_voter_state: shared_voter_state.clone(),
+			shared_authority_set: shared_authority_set.clone(),
+			justification_stream: grandpa_link.justification_stream(),
+			subscription_executor: SubscriptionTaskExecutor::new(task_manager.spawn_handle()),
+			finality_provider: FinalityProofProvider::new_for_service(
+				backend,
+				Some(shared_authority_set),
+			),
+		},
+	});
+
+	// The voter has not started yet.
+	assert_eq!(call(&io, "grandpa_diagnostics"), json!({
+		"setId": 0,
+		"bestNumber": 0,
+		"finalizedNumber": 0,
+		"lag": 0,
+		"round": null,
+	}));
+
+	shared_voter_state.reset(Box::new(Prevoted)).unwrap();
+	let alice = authority_keys_from_seed("Alice").1;
+	assert_eq!(call(&io, "grandpa_diagnostics")["round"], json!({
+		"round": 2,
+		"totalWeight": 1,
+		"thresholdWeight": 1,
+		"prevoteWeight": 1,
+		"precommitWeight": 0,
+		"authorities": [
+			{ "authority": alice.to_string(), "prevoted": true, "precommitted": false },
+		],
+	}));
+}
@@ -0,0 +1,69 @@
+use hyper::{Body, Request, StatusCode};
+use node_template::health::{handle, HealthStatus, HealthThresholds};
+use serde_json::Value;
+
+const THRESHOLDS: HealthThresholds =
+	HealthThresholds { min_peers: 1, max_finality_lag: 64, max_missed_slots: 3 };
+
+fn synced() -> HealthStatus {
+	HealthStatus {
+		peers: 3,
+		is_syncing: false,
+		best_number: 100,
+		finalized_number: 98,
+		missed_slots: Some(0),
+	}
+}
+
+#[test]
+fn synced_node_is_healthy_and_ready() {
+	assert!(synced().health_problems(&THRESHOLDS).is_empty());
+	assert!(synced().readiness_problems(&THRESHOLDS).is_empty());
+}
+
+#[test]
+fn thresholds_make_node_unhealthy() {
+	let status = HealthStatus { peers: 0, finalized_number: 30, missed_slots: Some(4), ..synced() };
+	assert_eq!(status.health_problems(&THRESHOLDS), vec![
+		"0 peers connected, fewer than 1".to_string(),
+		"finality lags 70 blocks behind, more than 64".to_string(),
+		"the local authority missed 4 consecutive slots, more than 3".to_string(),
+	]);
+	assert_eq!(status.readiness_problems(&THRESHOLDS), vec![
+		"0 peers connected, fewer than 1".to_string(),
+	]);
+}
+
+#[test]
+fn syncing_node_is_healthy_but_not_ready() {
+	let status = HealthStatus { is_syncing: true, finalized_number: 0, ..synced() };
+	assert!(status.health_problems(&THRESHOLDS).is_empty());
+	assert_eq!(status.readiness_problems(&THRESHOLDS), vec!["the node is syncing".to_string()]);
+}
+
+#[test]
+fn endpoints_report_status() {
+	let get = |path: &str, status: HealthStatus| {
+		let request = Request::get(path).body(Body::empty()).unwrap();
+		let response = handle(&request, || status.clone(), &THRESHOLDS);
+		let code = response.status();
+		let body = futures::executor::block_on(hyper::body::to_bytes(response.into_body()))
+			.unwrap();
+		(code, serde_json::from_slice::<Value>(&body).ok())
+	};
+
+	let (code, report) = get("/health", synced());
+	assert_eq!(code, StatusCode::OK);
+	let report = report.unwrap();
+	assert_eq!(report["healthy"], true);
+	assert_eq!(report["peers"], 3);
+	assert_eq!(report["bestNumber"], 100);
+	assert_eq!(report["missedSlots"], 0);
+
+	let syncing = HealthStatus { is_syncing: true, ..synced() };
+	let (code, report) = get("/ready", syncing);
+	assert_eq!(code, StatusCode::SERVICE_UNAVAILABLE);
+	assert_eq!(report.unwrap()["problems"][0], "the node is syncing");
+
+	assert_eq!(get("/metrics", synced()).0, StatusCode::NOT_FOUND);
+}
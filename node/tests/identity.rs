@@ -0,0 +1,73 @@
+//! Runs a development node, sets identities on it and resolves them through
+//! `identity_displayNames`.
+
+mod common;
+
+use common::{start, Runtime};
+use futures::compat::Future01CompatExt;
+use jsonrpc_core::ErrorCode;
+use jsonrpc_core_client::RpcError;
+use node_template::{
+	cmd::{sign, Node},
+	rpc::identity::{gen_client::Client as IdentityClient, MAX_ACCOUNTS},
+};
+use node_template_runtime::{AccountId, Call, Hash};
+use pallet_identity::{Data, IdentityInfo};
+use sp_core::{sr25519, Pair};
+use tempfile::tempdir;
+
+fn raw(name: &str) -> Data {
+	Data::Raw(name.as_bytes().to_vec())
+}
+
+#[test]
+fn display_names_of_identities_and_sub_accounts() {
+	let path = tempdir().unwrap();
+	let _node = start(&[
+		"--dev",
+		"--base-path", path.path().to_str().unwrap(),
+		"--port", "30435",
+		"--ws-port", "19946",
+	]);
+
+	let mut runtime = Runtime::new();
+	let channel = runtime.channel("ws://127.0.0.1:19946");
+	let alice = sr25519::Pair::from_string("//Alice", None).unwrap();
+	let account = |seed| AccountId::from(sr25519::Pair::from_string(seed, None).unwrap().public());
+	let (bob, charlie) = (account("//Bob"), account("//Charlie"));
+
+	let stash = bob.clone();
+	runtime.block_on(async move {
+		let node = Node::connect("ws://127.0.0.1:19946").await?;
+		let nonce = node.nonce(alice.public().into()).await?;
+		let chain = node.chain_state(0).await?;
+		let info = IdentityInfo {
+			additional: vec![],
+			display: raw("Alice"),
+			legal: Data::None,
+			web: Data::None,
+			riot: Data::None,
+			email: Data::None,
+			pgp_fingerprint: None,
+			image: Data::None,
+			twitter: Data::None,
+		};
+		let set_identity = Call::Identity(pallet_identity::Call::set_identity(info));
+		node.submit_and_watch(&sign(&alice, set_identity, nonce, 0, &chain), false).await?;
+		let set_subs = Call::Identity(pallet_identity::Call::set_subs(vec![(stash, raw("stash"))]));
+		node.submit_and_watch(&sign(&alice, set_subs, nonce + 1, 0, &chain), false).await
+	}).unwrap();
+
+	let client: IdentityClient<Hash> = channel.into();
+	let alice = account("//Alice");
+	let accounts = vec![alice.clone(), bob, charlie];
+	let names = runtime.block_on(client.display_names(accounts, None).compat());
+	assert_eq!(names.unwrap(), vec![Some("Alice".into()), Some("Alice/stash".into()), None]);
+
+	// Batches are capped.
+	let accounts = vec![alice; MAX_ACCOUNTS + 1];
+	match runtime.block_on(client.display_names(accounts, None).compat()) {
+		Err(RpcError::JsonRpcError(e)) => assert_eq!(e.code, ErrorCode::InvalidParams),
+		names => panic!("expected invalid params, got {:?}", names),
+	}
+}
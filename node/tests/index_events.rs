@@ -0,0 +1,101 @@
+use frame_support::weights::{DispatchClass, DispatchInfo, Pays};
+use frame_system::{EventRecord, Phase};
+use node_template::cmd::{decode::ToJson, event_fields, EventDescriptions};
+use node_template_runtime::{AccountId, Event, FixedPointNumber, FixedU128, Hash};
+use serde_json::json;
+use sp_core::crypto::Ss58Codec;
+use sp_runtime::DispatchError;
+
+#[test]
+fn events_are_indexed_by_pallet_and_variant() {
+	let descriptions = EventDescriptions::of_runtime();
+	let (alice, bob) = (AccountId::from([1; 32]), AccountId::from([2; 32]));
+	let record = EventRecord {
+		phase: Phase::ApplyExtrinsic(2),
+		event: Event::pallet_balances(pallet_balances::Event::Transfer(alice.clone(), bob.clone(), 10)),
+		topics: vec![],
+	};
+
+	let indexed = descriptions.index(7, Hash::repeat_byte(7), &record);
+	assert_eq!(indexed.block, 7);
+	assert_eq!(indexed.extrinsic, Some(2));
+	assert_eq!(indexed.phase, "applyExtrinsic");
+	assert_eq!(indexed.pallet, "Balances");
+	assert_eq!(indexed.variant, "Transfer");
+	assert_eq!(indexed.fields, vec![
+		json!({ "type": "AccountId", "value": alice.to_ss58check() }),
+		json!({ "type": "AccountId", "value": bob.to_ss58check() }),
+		json!({ "type": "Balance", "value": "10" }),
+	]);
+}
+
+#[test]
+fn fields_are_written_from_the_native_events() {
+	let alice = AccountId::from([1; 32]);
+	let info = DispatchInfo { weight: 10, class: DispatchClass::Normal, pays_fee: Pays::Yes };
+
+	let event = Event::frame_system(frame_system::Event::ExtrinsicSuccess(info));
+	assert_eq!(event_fields(&event), Some(vec![
+		json!({ "weight": 10, "class": "Normal", "paysFee": "Yes" }),
+	]));
+
+	let event = Event::pallet_nft(pallet_nft::Event::Minted(3, 4, alice.clone()));
+	assert_eq!(event_fields(&event), Some(vec![json!(3), json!(4), alice.to_json()]));
+
+	let event = Event::pallet_assets(pallet_assets::Event::MetadataSet(
+		7,
+		b"Tether".to_vec(),
+		b"USDT".to_vec(),
+		6,
+		false,
+	));
+	assert_eq!(event_fields(&event), Some(vec![
+		json!(7),
+		json!("Tether"),
+		json!("USDT"),
+		json!(6),
+		json!(false),
+	]));
+
+	let values = vec![(1, FixedU128::from_inner(1_500_000_000_000_000_000))];
+	let event = Event::pallet_oracle(pallet_oracle::Event::NewFeedData(alice.clone(), values));
+	assert_eq!(event_fields(&event), Some(vec![
+		json!(alice.to_ss58check()),
+		json!([[1, "1.500000000000000000"]]),
+	]));
+}
+
+#[test]
+fn values_are_written_as_their_native_types() {
+	assert_eq!(u128::MAX.to_json(), json!(u128::MAX.to_string()));
+	assert_eq!(Some(3u32).to_json(), json!(3));
+	assert_eq!(None::<u32>.to_json(), json!(null));
+	assert_eq!(Ok::<(), DispatchError>(()).to_json(), json!({ "ok": null }));
+	assert_eq!(
+		Err::<(), _>(DispatchError::BadOrigin).to_json(),
+		json!({ "err": "BadOrigin" }),
+	);
+	assert_eq!(Hash::repeat_byte(0xab).to_json(), json!(format!("0x{}", "ab".repeat(32))));
+}
+
+#[test]
+fn assets_events_are_written_with_their_field_names() {
+	let descriptions = EventDescriptions::of_runtime();
+	let (alice, bob) = (AccountId::from([1; 32]), AccountId::from([2; 32]));
+	let transferred = pallet_assets::Event::Transferred(7, alice.clone(), bob.clone(), 1_000);
+	let record = EventRecord {
+		phase: Phase::ApplyExtrinsic(1),
+		event: Event::pallet_assets(transferred),
+		topics: vec![],
+	};
+
+	let indexed = descriptions.index(3, Hash::repeat_byte(3), &record);
+	assert_eq!(indexed.pallet, "Assets");
+	assert_eq!(indexed.variant, "Transferred");
+	assert_eq!(indexed.fields, vec![
+		json!({ "type": "AssetId", "value": 7 }),
+		json!({ "type": "AccountId", "value": alice.to_ss58check() }),
+		json!({ "type": "AccountId", "value": bob.to_ss58check() }),
+		json!({ "type": "Balance", "value": "1000" }),
+	]);
+}
@@ -0,0 +1,87 @@
+use node_template::cmd::{storage::StorageItems, ExtrinsicDisplay, StorageKeyDisplay};
+use node_template_runtime::{
+	extensions::ChargeTransactionPaymentByTip, pallet_template, AccountId, Address, Call,
+	SignedExtra, TimestampCall, UncheckedExtrinsic,
+};
+use sp_core::{blake2_128, crypto::Ss58Codec, sr25519, twox_128};
+use sp_runtime::{generic::Era, MultiSignature};
+
+fn signed_extra(era: Era, nonce: u32, tip: u128) -> SignedExtra {
+	(
+		frame_system::CheckSpecVersion::new(),
+		frame_system::CheckTxVersion::new(),
+		frame_system::CheckGenesis::new(),
+		frame_system::CheckEra::from(era),
+		frame_system::CheckNonce::from(nonce),
+		frame_system::CheckWeight::new(),
+		ChargeTransactionPaymentByTip::from(tip),
+		pallet_template::CheckFreeCall::new(),
+	)
+}
+
+#[test]
+fn signed_extrinsics_show_their_signer_and_extensions() {
+	let signer = AccountId::from([1; 32]);
+	let extrinsic = UncheckedExtrinsic::new_signed(
+		Call::TemplateModule(pallet_template::Call::do_something(42)),
+		Address::Id(signer.clone()),
+		MultiSignature::Sr25519(sr25519::Signature::from_raw([0; 64])),
+		signed_extra(Era::mortal(64, 10), 3, 100),
+	);
+
+	let shown = ExtrinsicDisplay(&extrinsic).to_string();
+	assert!(shown.contains(&format!("Signer: {}\n", signer.to_ss58check())), "{}", shown);
+	assert!(shown.contains("Era: mortal, period 64, phase 10\n"), "{}", shown);
+	assert!(shown.contains("Nonce: 3\n"), "{}", shown);
+	assert!(shown.contains("Tip: 100\n"), "{}", shown);
+	assert!(shown.contains("do_something"), "{}", shown);
+}
+
+#[test]
+fn unsigned_extrinsics_show_their_call() {
+	let extrinsic = UncheckedExtrinsic::new_unsigned(Call::Timestamp(TimestampCall::set(1_000)));
+
+	let shown = ExtrinsicDisplay(&extrinsic).to_string();
+	assert!(shown.contains("Unsigned\n"), "{}", shown);
+	assert!(shown.contains("set"), "{}", shown);
+}
+
+#[test]
+fn storage_keys_are_named_and_decoded() {
+	let items = StorageItems::of_runtime();
+	let account = AccountId::from([1; 32]);
+	let key = [
+		&twox_128(b"System")[..],
+		&twox_128(b"Account")[..],
+		&blake2_128(account.as_ref())[..],
+		account.as_ref(),
+	].concat();
+
+	let shown = StorageKeyDisplay(&items, &key).to_string();
+	assert!(shown.contains("Storage item: System::Account\n"), "{}", shown);
+	assert!(shown.contains(&account.to_ss58check()), "{}", shown);
+
+	let shown = StorageKeyDisplay(&items, b":code").to_string();
+	assert_eq!(shown, "Well known key: :code\n");
+}
+
+#[test]
+fn the_keys_of_all_runtime_storage_items_are_decoded() {
+	// Add the native types of the keys of new storage maps to `storage::key_decoders`.
+	assert_eq!(StorageItems::of_runtime().undecoded(), Vec::<String>::new());
+}
+
+#[test]
+fn undecoded_storage_keys_are_labelled() {
+	let items = StorageItems::of_runtime();
+	let key = [
+		&twox_128(b"System")[..],
+		&twox_128(b"Account")[..],
+		&[0; 16][..],
+		&[1, 2, 3][..],
+	].concat();
+
+	let shown = StorageKeyDisplay(&items, &key).to_string();
+	assert!(shown.contains("\"undecoded\""), "{}", shown);
+	assert!(shown.contains("0x010203"), "{}", shown);
+}
@@ -0,0 +1,68 @@
+//! Runs a development full node and a light client connected to it, and checks the light client
+//! answers the custom RPCs with the state of the full node.
+
+mod common;
+
+use std::time::Duration;
+
+use common::{eventually, start, Runtime};
+use futures::compat::Future01CompatExt;
+use node_template::{
+	cmd::{sign, Node, TxCall},
+	rpc::template::gen_client::Client as TemplateClient,
+};
+use node_template_runtime::{AccountId, Hash, Index};
+use sp_core::{sr25519, Pair};
+use substrate_frame_rpc_system::SystemClient;
+use tempfile::tempdir;
+
+/// The peer id of the full node, derived from its `--node-key`.
+const FULL_NODE_KEY: &str = "0000000000000000000000000000000000000000000000000000000000000001";
+const FULL_NODE_PEER_ID: &str = "12D3KooWEyoppNCUx8Yx66oV9fJnriXwCcXwDDUA2kj6vnc6iDEp";
+
+#[test]
+fn light_client_reads_the_state_of_the_full_node() {
+	let (full_path, light_path) = (tempdir().unwrap(), tempdir().unwrap());
+	let _full = start(&[
+		"--dev",
+		"--base-path", full_path.path().to_str().unwrap(),
+		"--port", "30433",
+		"--ws-port", "19944",
+		"--node-key", FULL_NODE_KEY,
+	]);
+	let bootnode = format!("/ip4/127.0.0.1/tcp/30433/p2p/{}", FULL_NODE_PEER_ID);
+	let _light = start(&[
+		"--light",
+		"--chain", "dev",
+		"--base-path", light_path.path().to_str().unwrap(),
+		"--port", "30434",
+		"--ws-port", "19945",
+		"--bootnodes", &bootnode,
+	]);
+
+	// Store a value on the full node, and wait for it to be finalized.
+	let mut runtime = Runtime::new();
+	let alice = sr25519::Pair::from_string("//Alice", None).unwrap();
+	let account = AccountId::from(alice.public());
+	let full = runtime.channel("ws://127.0.0.1:19944");
+	let signer = account.clone();
+	runtime.block_on(async move {
+		let node = Node::connect("ws://127.0.0.1:19944").await?;
+		let nonce = node.nonce(signer).await?;
+		let chain = node.chain_state(0).await?;
+		let call = TxCall::DoSomething { something: 42 }.to_call();
+		node.submit_and_watch(&sign(&alice, call, nonce, 0, &chain), true).await
+	}).unwrap();
+	let full_template: TemplateClient<Hash> = full.into();
+	assert_eq!(runtime.block_on(full_template.something(None).compat()).unwrap(), Some(42));
+
+	// The light client proves the value and the nonce against the headers it synced.
+	let light = runtime.channel("ws://127.0.0.1:19945");
+	let light_template: TemplateClient<Hash> = light.clone().into();
+	let light_system: SystemClient<AccountId, Index> = light.into();
+	eventually(Duration::from_secs(120), || {
+		let something = runtime.block_on(light_template.something(None).compat()).ok()?;
+		(something == Some(42)).then(|| ())
+	});
+	assert_eq!(runtime.block_on(light_system.nonce(account).compat()).unwrap(), 1);
+}
@@ -0,0 +1,110 @@
+use std::{
+	io,
+	sync::{Arc, Mutex},
+};
+
+use node_template::{cli::Cli, logging::JsonFormat};
+use sc_cli::SubstrateCli;
+use serde_json::{json, Map, Value};
+use structopt::StructOpt;
+use tracing::Level;
+
+const HASH: &str = "0x3c5a9b1e2f0d4c6b8a7e9f1d2c3b4a5968776655443322110ffeeddccbbaa998";
+const PEER_ID: &str = "12D3KooWEyoppNCUx8Yx66oV9fJnriXwCcXwDDUA2kj6vnc6iDEp";
+
+fn message(message: &str) -> Map<String, Value> {
+	let mut fields = Map::new();
+	fields.insert("message".into(), message.into());
+	fields
+}
+
+#[test]
+fn blocks_and_peers_are_extracted_from_messages() {
+	let format = JsonFormat::new();
+
+	let record = format.record(&Level::INFO, "substrate", message(&format!(
+		"✨ Imported #42 ({})",
+		HASH,
+	)));
+	assert_eq!(record["level"], "INFO");
+	assert_eq!(record["target"], "substrate");
+	assert_eq!(record["blockNumber"], 42);
+	assert_eq!(record["blockHash"], HASH);
+	assert!(record.get("peerId").is_none());
+
+	let record = format.record(&Level::DEBUG, "sync", message(&format!(
+		"Block announce from {}: #7 (0x3c5a…a998)",
+		PEER_ID,
+	)));
+	assert_eq!(record["blockNumber"], 7);
+	assert_eq!(record["blockHash"], "0x3c5a…a998");
+	assert_eq!(record["peerId"], PEER_ID);
+}
+
+#[test]
+fn recorded_fields_take_precedence() {
+	let mut fields = message("Finalized #12");
+	fields.insert("blockNumber".into(), 10.into());
+	let record = JsonFormat::new().record(&Level::INFO, "afg", fields);
+	assert_eq!(record["blockNumber"], 10);
+	assert_eq!(record["message"], "Finalized #12");
+}
+
+#[derive(Clone, Default)]
+struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+impl io::Write for Buffer {
+	fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+		self.0.lock().unwrap().write(data)
+	}
+
+	fn flush(&mut self) -> io::Result<()> {
+		Ok(())
+	}
+}
+
+#[test]
+fn events_are_written_as_json_lines() {
+	let buffer = Buffer::default();
+	let writer = buffer.clone();
+	let subscriber = tracing_subscriber::fmt()
+		.with_writer(move || writer.clone())
+		.event_format(JsonFormat::new())
+		.finish();
+
+	tracing::subscriber::with_default(subscriber, || {
+		tracing::info!(target: "runtime::template", who = "Alice", "Something set to {}", 42);
+		tracing::warn!(target: "aura", slots = 3u64, "Missed slots");
+	});
+
+	let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+	let mut records = output.lines()
+		.map(|line| serde_json::from_str::<Value>(line).unwrap())
+		.map(|mut record| {
+			record.as_object_mut().unwrap().remove("timestamp").unwrap();
+			record
+		});
+	assert_eq!(records.next(), Some(json!({
+		"level": "INFO",
+		"target": "runtime::template",
+		"message": "Something set to 42",
+		"who": "Alice",
+	})));
+	assert_eq!(records.next(), Some(json!({
+		"level": "WARN",
+		"target": "aura",
+		"message": "Missed slots",
+		"slots": 3,
+	})));
+	assert_eq!(records.next(), None);
+}
+
+#[test]
+fn options_the_json_logger_ignores_are_rejected() {
+	let flags: &[&[&str]] = &[&["--tracing-targets", "sync"], &["--enable-log-reloading"]];
+	for flags in flags {
+		let args = ["node-template", "--dev", "--tmp", "--log-format", "json"];
+		let cli = Cli::from_iter(args.iter().chain(flags.iter()));
+		assert!(cli.create_runner(&cli.run).is_err());
+	}
+}
@@ -0,0 +1,169 @@
+use std::sync::Arc;
+
+use codec::{Decode, Encode};
+use futures::{executor::block_on, future::{ready, Ready}};
+use node_template::pool::{evict_lowest_priority, eviction_threshold};
+use node_template_runtime::{
+	extensions::priority, opaque::Block, AccountId, Balance, BlockNumber, Hash,
+};
+use sc_transaction_graph::{base_pool::Limit, error, ChainApi, ExtrinsicFor, Options, Pool};
+use sp_core::crypto::AccountId32;
+use sp_runtime::{
+	generic::BlockId,
+	traits::{BlakeTwo256, Hash as _},
+	transaction_validity::{TransactionSource, TransactionValidity, ValidTransaction},
+	OpaqueExtrinsic,
+};
+use sp_transaction_pool::InPoolTransaction;
+
+/// The fields of a transaction that determine its priority.
+#[derive(Encode, Decode)]
+struct TestTransaction {
+	sender: AccountId,
+	tip: Balance,
+	fee: Balance,
+	weight: u64,
+}
+
+impl TestTransaction {
+	fn new(sender: u8, tip: Balance) -> Self {
+		Self { sender: AccountId32::new([sender; 32]), tip, fee: 1_000, weight: 100_000 }
+	}
+
+	fn opaque(&self) -> OpaqueExtrinsic {
+		OpaqueExtrinsic::decode(&mut &self.encode().encode()[..]).unwrap()
+	}
+
+	fn from_opaque(uxt: &OpaqueExtrinsic) -> Self {
+		let bytes = Vec::<u8>::decode(&mut &uxt.encode()[..]).unwrap();
+		Self::decode(&mut &bytes[..]).unwrap()
+	}
+}
+
+/// A chain stuck at genesis, on which every transaction is valid.
+struct TestApi;
+
+impl ChainApi for TestApi {
+	type Block = Block;
+	type Error = error::Error;
+	type ValidationFuture = Ready<error::Result<TransactionValidity>>;
+	type BodyFuture = Ready<error::Result<Option<Vec<OpaqueExtrinsic>>>>;
+
+	fn validate_transaction(
+		&self,
+		_at: &BlockId<Block>,
+		_source: TransactionSource,
+		uxt: ExtrinsicFor<Self>,
+	) -> Self::ValidationFuture {
+		let tx = TestTransaction::from_opaque(&uxt);
+		ready(Ok(Ok(ValidTransaction {
+			priority: priority(tx.fee, tx.tip, tx.weight),
+			provides: vec![tx.sender.encode()],
+			..Default::default()
+		})))
+	}
+
+	fn block_id_to_number(&self, at: &BlockId<Block>) -> error::Result<Option<BlockNumber>> {
+		Ok(match at {
+			BlockId::Number(number) => Some(*number),
+			BlockId::Hash(_) => Some(0),
+		})
+	}
+
+	fn block_id_to_hash(&self, at: &BlockId<Block>) -> error::Result<Option<Hash>> {
+		Ok(match at {
+			BlockId::Number(_) => Some(Default::default()),
+			BlockId::Hash(hash) => Some(*hash),
+		})
+	}
+
+	fn hash_and_length(&self, uxt: &ExtrinsicFor<Self>) -> (Hash, usize) {
+		let encoded = uxt.encode();
+		(BlakeTwo256::hash(&encoded), encoded.len())
+	}
+
+	fn block_body(&self, _at: &BlockId<Block>) -> Self::BodyFuture {
+		ready(Ok(None))
+	}
+}
+
+/// A pool holding at most `ready_limit` ready transactions.
+fn pool(ready_limit: usize) -> Pool<TestApi> {
+	let limit = Limit { count: ready_limit, total_bytes: usize::MAX };
+	let options = Options { ready: limit.clone(), future: limit, ..Default::default() };
+	Pool::new(options, true.into(), Arc::new(TestApi))
+}
+
+fn submit(pool: &Pool<TestApi>, tx: &TestTransaction) -> error::Result<Hash> {
+	block_on(pool.submit_one(&BlockId::number(0), TransactionSource::External, tx.opaque()))
+}
+
+fn ready_tips(pool: &Pool<TestApi>) -> Vec<Balance> {
+	let mut tips = pool.validated_pool().ready()
+		.map(|tx| TestTransaction::from_opaque(tx.data()).tip)
+		.collect::<Vec<_>>();
+	tips.sort();
+	tips
+}
+
+/// Tips handed out in a scrambled order, so that age and priority don't line up.
+fn scrambled(count: u8) -> impl Iterator<Item = TestTransaction> {
+	(0..count).map(move |sender| {
+		let tip = Balance::from(sender) * 37 % Balance::from(count) * 1_000;
+		TestTransaction::new(sender, tip)
+	})
+}
+
+#[test]
+fn flooded_pool_keeps_the_highest_tips() {
+	let pool = pool(8192);
+	for tx in scrambled(50) {
+		submit(&pool, &tx).unwrap();
+	}
+	assert_eq!(pool.validated_pool().status().ready, 50);
+
+	let evicted = evict_lowest_priority(&pool, 10);
+
+	assert_eq!(evicted.len(), 40);
+	assert_eq!(pool.validated_pool().status().ready, 10);
+	assert_eq!(ready_tips(&pool), (40..50).map(|tip| tip * 1_000).collect::<Vec<Balance>>());
+
+	// Below the threshold, nothing is evicted.
+	assert!(evict_lowest_priority(&pool, 10).is_empty());
+}
+
+#[test]
+fn evicted_transactions_can_be_submitted_again() {
+	let pool = pool(8192);
+	let low = TestTransaction::new(0, 0);
+	let high = TestTransaction::new(1, 1_000);
+	let low_hash = submit(&pool, &low).unwrap();
+	submit(&pool, &high).unwrap();
+
+	assert_eq!(evict_lowest_priority(&pool, 1), vec![low_hash]);
+	assert_eq!(ready_tips(&pool), vec![1_000]);
+
+	// Evicted transactions are not banned.
+	assert_eq!(submit(&pool, &low).unwrap(), low_hash);
+	assert_eq!(ready_tips(&pool), vec![0, 1_000]);
+}
+
+#[test]
+fn eviction_runs_before_the_pool_limit() {
+	// Past its limit, the pool itself drops the oldest transactions, whatever their tip.
+	let unevicted = pool(10);
+	for tx in scrambled(30) {
+		let _ = submit(&unevicted, &tx);
+	}
+	assert_eq!(unevicted.validated_pool().status().ready, 10);
+	let highest = (20..30).map(|tip| tip * 1_000).collect::<Vec<Balance>>();
+	assert_ne!(ready_tips(&unevicted), highest);
+
+	// Evicting after each import keeps the pool below its limit, with the highest tips.
+	let evicted = pool(10);
+	for tx in scrambled(30) {
+		submit(&evicted, &tx).unwrap();
+		evict_lowest_priority(&evicted, eviction_threshold(10));
+	}
+	assert_eq!(ready_tips(&evicted), highest[1..].to_vec());
+}
@@ -0,0 +1,88 @@
+use node_template::{chain_spec::authority_keys_from_seed, slots::{AuthorityStats, SlotTracker}};
+use sp_consensus_aura::sr25519::AuthorityId as AuraId;
+
+fn authorities() -> Vec<AuraId> {
+	vec![authority_keys_from_seed("Alice").0, authority_keys_from_seed("Bob").0]
+}
+
+#[test]
+fn authored_and_missed_slots_are_counted() {
+	let (alice, bob) = (authorities()[0].clone(), authorities()[1].clone());
+	let mut tracker = SlotTracker::new(None);
+
+	// Slot 10 is Alice's, then Bob misses 11 and Alice authors 12.
+	tracker.record_block(None, 10, &authorities(), |_| false);
+	tracker.record_block(Some(10), 12, &authorities(), |_| false);
+	assert_eq!(tracker.stats(&alice), AuthorityStats { assigned: 2, authored: 2 });
+	assert_eq!(tracker.stats(&bob), AuthorityStats { assigned: 1, authored: 0 });
+
+	// Slots 13 to 18 are empty, Bob authors 19.
+	tracker.record_block(Some(12), 19, &authorities(), |_| false);
+	assert_eq!(tracker.stats(&alice), AuthorityStats { assigned: 5, authored: 2 });
+	assert_eq!(tracker.stats(&bob), AuthorityStats { assigned: 5, authored: 1 });
+	assert_eq!(tracker.stats(&bob).missed(), 4);
+}
+
+#[test]
+fn consecutive_misses_of_local_authorities_are_tracked() {
+	let (alice, bob) = (authorities()[0].clone(), authorities()[1].clone());
+	let is_bob = |authority: &AuraId| *authority == authorities()[1];
+	let mut tracker = SlotTracker::new(None);
+
+	tracker.record_block(Some(9), 10, &authorities(), is_bob);
+	tracker.record_block(Some(10), 12, &authorities(), is_bob);
+	tracker.record_block(Some(12), 14, &authorities(), is_bob);
+	assert_eq!(tracker.consecutive_missed(&bob), 2);
+	assert_eq!(tracker.consecutive_missed(&alice), 0);
+	assert_eq!(tracker.local_consecutive_missed(), 2);
+	assert_eq!(tracker.last_missed(&bob), Some(13));
+	assert_eq!(tracker.last_missed(&alice), None);
+
+	tracker.record_block(Some(14), 15, &authorities(), is_bob);
+	assert_eq!(tracker.consecutive_missed(&bob), 0);
+	assert_eq!(tracker.local_consecutive_missed(), 0);
+}
+
+#[test]
+fn the_last_slot_missed_is_the_last_one_assigned() {
+	let charlie = authority_keys_from_seed("Charlie").0;
+	let mut authorities = authorities();
+	authorities.push(charlie.clone());
+	let (alice, bob) = (authorities[0].clone(), authorities[1].clone());
+	let mut tracker = SlotTracker::new(None);
+
+	// Slots 31 to 37 are empty: Bob's last one is 37, Charlie's 35 and Alice's 36.
+	tracker.record_block(Some(30), 38, &authorities, |_| true);
+	assert_eq!(tracker.last_missed(&bob), Some(37));
+	assert_eq!(tracker.last_missed(&charlie), Some(35));
+	assert_eq!(tracker.last_missed(&alice), Some(36));
+	assert_eq!(tracker.consecutive_missed(&bob), 3);
+	assert_eq!(tracker.consecutive_missed(&alice), 2);
+	// Charlie authored 38.
+	assert_eq!(tracker.consecutive_missed(&charlie), 0);
+}
+
+#[test]
+fn slots_after_the_best_block_count_when_the_chain_stalls() {
+	let bob = authorities()[1].clone();
+	let is_bob = |authority: &AuraId| *authority == bob;
+	let mut tracker = SlotTracker::new(None);
+
+	// Nothing is known before the first block.
+	assert_eq!(tracker.local_consecutive_missed_at(20, is_bob), 0);
+
+	// Bob authors 11, then no block is imported.
+	tracker.record_block(Some(10), 11, &authorities(), is_bob);
+	assert_eq!(tracker.local_consecutive_missed_at(12, is_bob), 0);
+	// The current slot 13 may still get a block.
+	assert_eq!(tracker.local_consecutive_missed_at(13, is_bob), 0);
+	assert_eq!(tracker.local_consecutive_missed_at(14, is_bob), 1);
+	assert_eq!(tracker.local_consecutive_missed_at(18, is_bob), 3);
+	// A clock behind the best block counts nothing more.
+	assert_eq!(tracker.local_consecutive_missed_at(5, is_bob), 0);
+
+	// Alice authors 14 after Bob missed 13, and the chain stalls again.
+	tracker.record_block(Some(11), 14, &authorities(), is_bob);
+	assert_eq!(tracker.consecutive_missed(&bob), 1);
+	assert_eq!(tracker.local_consecutive_missed_at(18, is_bob), 3);
+}
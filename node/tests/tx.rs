@@ -0,0 +1,102 @@
+use codec::Encode;
+use node_template::cmd::{
+	extrinsic, sign, signed_payload, ChainState, ExtrinsicDisplay, TxCall, UnsignedTransaction,
+};
+use node_template_runtime::{Address, Hash, SignedExtra};
+use sp_core::{crypto::Ss58Codec, sr25519, Bytes, Pair};
+use sp_runtime::{
+	generic::{Era, SignedPayload},
+	traits::Verify,
+	MultiAddress, MultiSignature,
+};
+use structopt::StructOpt;
+
+fn chain() -> ChainState {
+	ChainState {
+		genesis_hash: Hash::repeat_byte(1),
+		spec_version: 100,
+		transaction_version: 1,
+		era: Era::mortal(64, 10),
+		birth_hash: Hash::repeat_byte(2),
+	}
+}
+
+#[test]
+fn transactions_sign_the_state_of_the_chain() {
+	let pair = sr25519::Pair::from_string("//Alice", None).unwrap();
+	let chain = chain();
+	let extrinsic = sign(&pair, TxCall::DoSomething { something: 42 }.to_call(), 3, 100, &chain);
+
+	let (address, signature, extra) = extrinsic.signature.clone().unwrap();
+	assert_eq!(address, Address::Id(pair.public().into()));
+	let payload = SignedPayload::<_, SignedExtra>::from_raw(
+		extrinsic.function.clone(),
+		extra,
+		(chain.spec_version, chain.transaction_version, chain.genesis_hash, chain.birth_hash, (), (), (), ()),
+	);
+	assert!(signature.verify(&payload.encode()[..], &pair.public().into()));
+
+	// Signing for another genesis gives a signature that doesn't verify here.
+	let other = sign(
+		&pair,
+		TxCall::DoSomething { something: 42 }.to_call(),
+		3,
+		100,
+		&ChainState { genesis_hash: Hash::repeat_byte(3), ..chain },
+	);
+	let (_, other_signature, _): (_, MultiSignature, _) = other.signature.unwrap();
+	assert!(!other_signature.verify(&payload.encode()[..], &pair.public().into()));
+}
+
+#[test]
+fn signed_extensions_carry_the_nonce_tip_and_era() {
+	let pair = sr25519::Pair::from_string("//Alice", None).unwrap();
+	let extrinsic = sign(&pair, TxCall::DoSomething { something: 42 }.to_call(), 3, 100, &chain());
+
+	let shown = ExtrinsicDisplay(&extrinsic).to_string();
+	assert!(shown.contains("Era: mortal, period 64, phase 10\n"), "{}", shown);
+	assert!(shown.contains("Nonce: 3\n"), "{}", shown);
+	assert!(shown.contains("Tip: 100\n"), "{}", shown);
+}
+
+#[test]
+fn prepared_transactions_sign_like_sent_ones() {
+	let pair = sr25519::Pair::from_string("//Alice", None).unwrap();
+	let call = TxCall::DoSomething { something: 42 };
+	let prepared = UnsignedTransaction {
+		signer: pair.public().into(),
+		call: Bytes(call.to_call().encode()),
+		nonce: 3,
+		tip: 100,
+		chain: chain(),
+	};
+
+	// The offline host only gets what `tx prepare` wrote.
+	let written = serde_json::to_string(&prepared).unwrap();
+	let read: UnsignedTransaction = serde_json::from_str(&written).unwrap();
+	assert_eq!(read, prepared);
+
+	let payload = signed_payload(read.call().unwrap(), read.nonce, read.tip, &read.chain);
+	let signature = payload.using_encoded(|payload| pair.sign(payload));
+	// sr25519 signatures are randomized, so compare what was signed rather than the signatures.
+	let offline = extrinsic(payload, read.signer, signature.into());
+	let sent = sign(&pair, call.to_call(), 3, 100, &chain());
+	assert_eq!(offline.function, sent.function);
+	assert_eq!(offline.signature.map(|(a, _, e)| (a, e)), sent.signature.map(|(a, _, e)| (a, e)));
+}
+
+#[test]
+fn transfers_are_made_to_addresses_or_account_indices() {
+	let alice = sr25519::Pair::from_string("//Alice", None).unwrap().public();
+	let dest = |dest: &str| match TxCall::from_iter(&["tx", "transfer", dest, "100"]) {
+		TxCall::Transfer { dest, value } => {
+			assert_eq!(value, 100);
+			dest
+		},
+		call => panic!("not a transfer: {:?}", call),
+	};
+
+	assert_eq!(dest(&alice.to_ss58check()), MultiAddress::Id(alice.into()));
+	assert_eq!(dest("5"), MultiAddress::Index(5));
+	assert!(TxCall::from_iter_safe(&["tx", "transfer", "alice", "100"]).is_err());
+}
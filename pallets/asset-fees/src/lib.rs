@@ -0,0 +1,308 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+/// Lets accounts pay their transaction fees in an asset of `pallet_assets`.
+///
+/// Fees are computed in the native currency as usual, then converted at a rate root sets for
+/// each asset accepted for fees. Accounts opt into paying with such an asset, and keep paying
+/// in the native currency otherwise. Fees paid in assets are split between `FeeCollector` and
+/// the block author, as the runtime splits native fees. The pallet also creates assets at
+/// genesis, which `pallet_assets` can't do on its own.
+
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+use frame_support::traits::{tokens::fungibles::Transfer, FindAuthor, Get};
+use pallet_transaction_payment::OnChargeTransaction;
+use sp_runtime::{
+	traits::{DispatchInfoOf, PostDispatchInfoOf, Saturating, Zero},
+	transaction_validity::{InvalidTransaction, TransactionValidityError},
+	Perbill,
+};
+use sp_std::marker::PhantomData;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use frame_support::{
+		dispatch::DispatchResult,
+		pallet_prelude::*,
+		traits::{tokens::fungibles::Mutate, UnfilteredDispatchable},
+	};
+	use frame_system::{pallet_prelude::*, RawOrigin};
+	use sp_runtime::{
+		traits::{AtLeast32BitUnsigned, SaturatedConversion, StaticLookup, Zero},
+		FixedPointNumber, FixedU128,
+	};
+	use sp_std::prelude::*;
+
+	/// Configure the pallet by specifying the parameters and types on which it depends.
+	///
+	/// Assets are created at genesis through `pallet_assets::force_create`, so its
+	/// `ForceOrigin` must accept the root origin.
+	#[pallet::config]
+	pub trait Config: frame_system::Config + pallet_assets::Config {
+		/// Because this pallet emits events, it depends on the runtime's definition of an event.
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+		/// The account receiving the fees paid in assets, but for the share of the block author.
+		type FeeCollector: Get<Self::AccountId>;
+		/// Finds the author of the current block.
+		type FindAuthor: FindAuthor<Self::AccountId>;
+		/// The share of the fees paid in assets going to the author of the block.
+		#[pallet::constant]
+		type AuthorShare: Get<Perbill>;
+	}
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	/// The amount of an asset paying for one unit of the native currency, for each asset
+	/// accepted for fees.
+	#[pallet::storage]
+	#[pallet::getter(fn conversion_rate)]
+	pub type ConversionRate<T: Config> = StorageMap<_, Twox64Concat, T::AssetId, FixedU128>;
+
+	/// The asset each account pays its fees with, if not the native currency.
+	#[pallet::storage]
+	#[pallet::getter(fn fee_asset)]
+	pub type FeeAsset<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, T::AssetId>;
+
+	#[pallet::genesis_config]
+	pub struct GenesisConfig<T: Config> {
+		/// The assets to create. [id, owner, is_sufficient, min_balance]
+		pub assets: Vec<(T::AssetId, T::AccountId, bool, T::Balance)>,
+		/// The balances to mint in the assets created. [id, who, balance]
+		pub balances: Vec<(T::AssetId, T::AccountId, T::Balance)>,
+		/// The assets accepted for fees. [id, rate]
+		pub conversion_rates: Vec<(T::AssetId, FixedU128)>,
+	}
+
+	#[cfg(feature = "std")]
+	impl<T: Config> Default for GenesisConfig<T> {
+		fn default() -> Self {
+			Self { assets: Vec::new(), balances: Vec::new(), conversion_rates: Vec::new() }
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
+		fn build(&self) {
+			for (id, owner, is_sufficient, min_balance) in &self.assets {
+				pallet_assets::Call::<T>::force_create(
+					*id,
+					T::Lookup::unlookup(owner.clone()),
+					*is_sufficient,
+					*min_balance,
+				)
+					.dispatch_bypass_filter(RawOrigin::Root.into())
+					.expect("`ForceOrigin` must accept root to create assets at genesis");
+			}
+			for (id, who, balance) in &self.balances {
+				<pallet_assets::Pallet<T> as Mutate<T::AccountId>>::mint_into(*id, who, *balance)
+					.expect("assets must be created before minting at genesis");
+			}
+			for (id, rate) in &self.conversion_rates {
+				<ConversionRate<T>>::insert(id, rate);
+			}
+		}
+	}
+
+	#[pallet::event]
+	#[pallet::metadata(
+		T::AccountId = "AccountId",
+		T::AssetId = "AssetId",
+		T::Balance = "Balance",
+		Option<T::AssetId> = "Option<AssetId>"
+	)]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// The conversion rate of an asset was set, or removed if `None`. [asset, rate]
+		ConversionRateSet(T::AssetId, Option<FixedU128>),
+		/// An account chose the asset it pays fees with, or the native currency if `None`.
+		/// [who, asset]
+		FeeAssetSet(T::AccountId, Option<T::AssetId>),
+		/// A fee was paid in an asset. [who, asset, amount]
+		AssetFeePaid(T::AccountId, T::AssetId, T::Balance),
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The asset is not accepted for fees.
+		NoConversionRate,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Set the amount of `asset` paying for one unit of the native currency, or stop
+		/// accepting `asset` for fees if `rate` is `None`.
+		///
+		/// The dispatch origin for this call must be _Root_.
+		#[pallet::weight(10_000 + T::DbWeight::get().writes(1))]
+		pub fn set_conversion_rate(
+			origin: OriginFor<T>,
+			#[pallet::compact] asset: T::AssetId,
+			rate: Option<FixedU128>,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+
+			match rate {
+				Some(rate) => <ConversionRate<T>>::insert(asset, rate),
+				None => <ConversionRate<T>>::remove(asset),
+			}
+
+			Self::deposit_event(Event::ConversionRateSet(asset, rate));
+			Ok(())
+		}
+
+		/// Pay the fees of the next transactions in `asset`, or in the native currency if
+		/// `asset` is `None`.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1, 1))]
+		pub fn set_fee_asset(origin: OriginFor<T>, asset: Option<T::AssetId>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			if let Some(asset) = asset {
+				ensure!(<ConversionRate<T>>::contains_key(asset), Error::<T>::NoConversionRate);
+			}
+			match asset {
+				Some(asset) => <FeeAsset<T>>::insert(&who, asset),
+				None => <FeeAsset<T>>::remove(&who),
+			}
+
+			Self::deposit_event(Event::FeeAssetSet(who, asset));
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// Convert `fee`, in the native currency, to an amount of `asset`.
+		///
+		/// Returns `None` if the asset is not accepted for fees, or if a non-zero fee would
+		/// convert to nothing.
+		pub fn to_asset<Balance>(asset: T::AssetId, fee: Balance) -> Option<T::Balance> where
+			Balance: AtLeast32BitUnsigned,
+		{
+			let rate = Self::conversion_rate(asset)?;
+			let amount = rate.saturating_mul_int(fee.saturated_into::<u128>()).saturated_into();
+			if amount.is_zero() && !fee.is_zero() {
+				return None
+			}
+			Some(amount)
+		}
+
+		/// The author of the current block, if it can be found.
+		pub fn block_author() -> Option<T::AccountId> {
+			let digest = <frame_system::Pallet<T>>::digest();
+			let pre_runtime_digests = digest.logs.iter().filter_map(|d| d.as_pre_runtime());
+			T::FindAuthor::find_author(pre_runtime_digests)
+		}
+	}
+}
+
+/// What `AssetOrNativeAdapter` withdrew to pay a fee.
+pub enum FeeLiquidity<T: Config, NativeInfo> {
+	/// The fee was paid in the native currency.
+	Native(NativeInfo),
+	/// The fee was paid with an amount of an asset.
+	Asset(T::AssetId, T::Balance),
+}
+
+impl<T: Config, NativeInfo: Default> Default for FeeLiquidity<T, NativeInfo> {
+	fn default() -> Self {
+		Self::Native(Default::default())
+	}
+}
+
+/// Charges fees in the asset an account chose with `set_fee_asset`, and through `Native`
+/// otherwise.
+///
+/// Fees paid in assets go to `FeeCollector`, which refunds the difference once the actual fee
+/// is known, then pays `AuthorShare` of the fee to the block author. Accounts whose asset is no
+/// longer accepted pay in the native currency.
+pub struct AssetOrNativeAdapter<T, Native>(PhantomData<(T, Native)>);
+
+impl<T, Native> OnChargeTransaction<T> for AssetOrNativeAdapter<T, Native> where
+	T: Config + pallet_transaction_payment::Config,
+	Native: OnChargeTransaction<T>,
+{
+	type Balance = Native::Balance;
+	type LiquidityInfo = FeeLiquidity<T, Native::LiquidityInfo>;
+
+	fn withdraw_fee(
+		who: &T::AccountId,
+		call: &T::Call,
+		info: &DispatchInfoOf<T::Call>,
+		fee: Self::Balance,
+		tip: Self::Balance,
+	) -> Result<Self::LiquidityInfo, TransactionValidityError> {
+		let asset_fee = Pallet::<T>::fee_asset(who)
+			.and_then(|asset| Pallet::<T>::to_asset(asset, fee).map(|amount| (asset, amount)));
+		let (asset, amount) = match asset_fee {
+			Some(asset_fee) => asset_fee,
+			None => return Native::withdraw_fee(who, call, info, fee, tip).map(FeeLiquidity::Native),
+		};
+
+		if !amount.is_zero() {
+			<pallet_assets::Pallet<T> as Transfer<T::AccountId>>::transfer(
+				asset,
+				who,
+				&T::FeeCollector::get(),
+				amount,
+				false,
+			).map_err(|_| InvalidTransaction::Payment)?;
+		}
+
+		Ok(FeeLiquidity::Asset(asset, amount))
+	}
+
+	fn correct_and_deposit_fee(
+		who: &T::AccountId,
+		info: &DispatchInfoOf<T::Call>,
+		post_info: &PostDispatchInfoOf<T::Call>,
+		corrected_fee: Self::Balance,
+		tip: Self::Balance,
+		already_withdrawn: Self::LiquidityInfo,
+	) -> Result<(), TransactionValidityError> {
+		let (asset, paid) = match already_withdrawn {
+			FeeLiquidity::Native(withdrawn) => return Native::correct_and_deposit_fee(
+				who, info, post_info, corrected_fee, tip, withdrawn,
+			),
+			FeeLiquidity::Asset(asset, paid) => (asset, paid),
+		};
+
+		let corrected = Pallet::<T>::to_asset(asset, corrected_fee).unwrap_or(paid).min(paid);
+		let refund = paid.saturating_sub(corrected);
+		// The transaction was paid for in full already, so failing to refund, e.g. because the
+		// refund is below the minimum balance of a reaped account, only forfeits the refund.
+		let refunded = !refund.is_zero() && <pallet_assets::Pallet<T> as Transfer<T::AccountId>>::transfer(
+			asset,
+			&T::FeeCollector::get(),
+			who,
+			refund,
+			false,
+		).is_ok();
+		let fee = if refunded { corrected } else { paid };
+
+		// Failing to pay the author, e.g. because its share is below the minimum balance of the
+		// asset, leaves the share with `FeeCollector`.
+		let to_author = T::AuthorShare::get() * fee;
+		if let Some(author) = Pallet::<T>::block_author().filter(|_| !to_author.is_zero()) {
+			let _ = <pallet_assets::Pallet<T> as Transfer<T::AccountId>>::transfer(
+				asset,
+				&T::FeeCollector::get(),
+				&author,
+				to_author,
+				false,
+			);
+		}
+
+		Pallet::<T>::deposit_event(Event::AssetFeePaid(who.clone(), asset, fee));
+		Ok(())
+	}
+}
@@ -0,0 +1,161 @@
+use crate as pallet_asset_fees;
+use sp_core::H256;
+use frame_support::{parameter_types, traits::FindAuthor, weights::IdentityFee};
+use frame_system::EnsureRoot;
+use pallet_transaction_payment::CurrencyAdapter;
+use sp_runtime::{
+	traits::{BlakeTwo256, IdentityLookup}, testing::Header, ConsensusEngineId, FixedPointNumber,
+	FixedU128, Perbill,
+};
+use frame_system as system;
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+// Configure a mock runtime to test the pallet.
+frame_support::construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+		TransactionPayment: pallet_transaction_payment::{Pallet, Storage},
+		Assets: pallet_assets::{Pallet, Call, Storage, Event<T>},
+		AssetFees: pallet_asset_fees::{Pallet, Call, Storage, Config<T>, Event<T>},
+	}
+);
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const SS58Prefix: u8 = 42;
+}
+
+impl system::Config for Test {
+	type BaseCallFilter = ();
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type Origin = Origin;
+	type Call = Call;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = pallet_balances::AccountData<u64>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = SS58Prefix;
+	type OnSetCode = ();
+}
+
+parameter_types! {
+	pub const ExistentialDeposit: u64 = 1;
+}
+
+impl pallet_balances::Config for Test {
+	type MaxLocks = ();
+	type Balance = u64;
+	type Event = Event;
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub const TransactionByteFee: u64 = 1;
+}
+
+/// Fees paid in the native currency are burned.
+pub type FeeAdapter = crate::AssetOrNativeAdapter<Test, CurrencyAdapter<Balances, ()>>;
+
+impl pallet_transaction_payment::Config for Test {
+	type OnChargeTransaction = FeeAdapter;
+	type TransactionByteFee = TransactionByteFee;
+	type WeightToFee = IdentityFee<u64>;
+	type FeeMultiplierUpdate = ();
+}
+
+parameter_types! {
+	pub const AssetDeposit: u64 = 1;
+	pub const ApprovalDeposit: u64 = 1;
+	pub const StringLimit: u32 = 50;
+	pub const MetadataDepositBase: u64 = 1;
+	pub const MetadataDepositPerByte: u64 = 1;
+}
+
+impl pallet_assets::Config for Test {
+	type Event = Event;
+	type Balance = u64;
+	type AssetId = u32;
+	type Currency = Balances;
+	type ForceOrigin = EnsureRoot<u64>;
+	type AssetDeposit = AssetDeposit;
+	type MetadataDepositBase = MetadataDepositBase;
+	type MetadataDepositPerByte = MetadataDepositPerByte;
+	type ApprovalDeposit = ApprovalDeposit;
+	type StringLimit = StringLimit;
+	type Freezer = ();
+	type Extra = ();
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub const FeeCollector: u64 = 100;
+	pub const AuthorShare: Perbill = Perbill::from_percent(20);
+}
+
+/// The author of every block in tests.
+pub const AUTHOR: u64 = 101;
+
+pub struct Author;
+
+impl FindAuthor<u64> for Author {
+	fn find_author<'a, I>(_digests: I) -> Option<u64> where
+		I: 'a + IntoIterator<Item=(ConsensusEngineId, &'a [u8])>,
+	{
+		Some(AUTHOR)
+	}
+}
+
+impl pallet_asset_fees::Config for Test {
+	type Event = Event;
+	type FeeCollector = FeeCollector;
+	type FindAuthor = Author;
+	type AuthorShare = AuthorShare;
+}
+
+/// An asset accepted for fees, at two units per unit of the native currency.
+pub const ASSET: u32 = 1;
+/// An asset not accepted for fees.
+pub const UNRATED: u32 = 2;
+/// The balance of every endowed account, in the native currency and in each asset.
+pub const INITIAL_BALANCE: u64 = 1_000;
+
+// Build genesis storage according to the mock runtime.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut t = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	pallet_balances::GenesisConfig::<Test> {
+		balances: vec![(1, INITIAL_BALANCE)],
+	}.assimilate_storage(&mut t).unwrap();
+	pallet_asset_fees::GenesisConfig::<Test> {
+		assets: vec![(ASSET, 1, true, 1), (UNRATED, 1, true, 1)],
+		balances: vec![(ASSET, 1, INITIAL_BALANCE), (ASSET, 2, INITIAL_BALANCE), (UNRATED, 2, INITIAL_BALANCE)],
+		conversion_rates: vec![(ASSET, FixedU128::saturating_from_integer(2))],
+	}.assimilate_storage(&mut t).unwrap();
+
+	let mut ext = sp_io::TestExternalities::new(t);
+	// Events are not deposited at genesis.
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
@@ -0,0 +1,159 @@
+use crate::{mock::*, Error, Event as AssetFeesEvent};
+use frame_support::{
+	assert_noop, assert_ok,
+	weights::{DispatchInfo, PostDispatchInfo},
+};
+use pallet_transaction_payment::OnChargeTransaction;
+use sp_runtime::{
+	traits::BadOrigin,
+	transaction_validity::InvalidTransaction,
+	FixedPointNumber, FixedU128,
+};
+
+fn remark() -> Call {
+	Call::System(frame_system::Call::remark(vec![]))
+}
+
+/// Charge `fee` to `who`, then correct it to `corrected_fee` as after dispatch.
+fn charge(who: u64, fee: u64, corrected_fee: u64) {
+	let info = DispatchInfo::default();
+	let withdrawn = FeeAdapter::withdraw_fee(&who, &remark(), &info, fee, 0).unwrap();
+	assert_ok!(FeeAdapter::correct_and_deposit_fee(
+		&who,
+		&info,
+		&PostDispatchInfo::default(),
+		corrected_fee,
+		0,
+		withdrawn,
+	));
+}
+
+#[test]
+fn genesis_creates_assets() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(Assets::balance(ASSET, 1), INITIAL_BALANCE);
+		assert_eq!(Assets::balance(ASSET, 2), INITIAL_BALANCE);
+		assert_eq!(Assets::balance(UNRATED, 2), INITIAL_BALANCE);
+		assert_eq!(AssetFees::conversion_rate(ASSET), Some(FixedU128::saturating_from_integer(2)));
+		assert_eq!(AssetFees::conversion_rate(UNRATED), None);
+	});
+}
+
+#[test]
+fn fees_are_paid_in_native_currency_by_default() {
+	new_test_ext().execute_with(|| {
+		charge(1, 100, 60);
+
+		assert_eq!(Balances::free_balance(1), INITIAL_BALANCE - 60);
+		assert_eq!(Assets::balance(ASSET, 1), INITIAL_BALANCE);
+	});
+}
+
+#[test]
+fn fees_are_paid_in_the_chosen_asset() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(AssetFees::set_fee_asset(Origin::signed(2), Some(ASSET)));
+		System::assert_last_event(AssetFeesEvent::FeeAssetSet(2, Some(ASSET)).into());
+
+		charge(2, 100, 60);
+
+		// Account 2 holds no native currency, it pays twice the fee in the asset.
+		assert_eq!(Assets::balance(ASSET, 2), INITIAL_BALANCE - 120);
+		System::assert_last_event(AssetFeesEvent::AssetFeePaid(2, ASSET, 120).into());
+
+		// The fee is split as native fees are.
+		assert_eq!(Assets::balance(ASSET, FeeCollector::get()), 96);
+		assert_eq!(Assets::balance(ASSET, AUTHOR), 24);
+	});
+}
+
+#[test]
+fn fees_are_refunded_in_the_asset_withdrawn() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(AssetFees::set_fee_asset(Origin::signed(2), Some(ASSET)));
+		let info = DispatchInfo::default();
+		let withdrawn = FeeAdapter::withdraw_fee(&2, &remark(), &info, 100, 0).unwrap();
+		assert_eq!(Assets::balance(ASSET, 2), INITIAL_BALANCE - 200);
+
+		// Changing the fee asset during dispatch doesn't change the asset refunded.
+		assert_ok!(AssetFees::set_fee_asset(Origin::signed(2), None));
+		assert_ok!(FeeAdapter::correct_and_deposit_fee(
+			&2,
+			&info,
+			&PostDispatchInfo::default(),
+			60,
+			0,
+			withdrawn,
+		));
+
+		assert_eq!(Assets::balance(ASSET, 2), INITIAL_BALANCE - 120);
+		assert_eq!(Balances::free_balance(2), 0);
+	});
+}
+
+#[test]
+fn insufficient_asset_balance_is_rejected() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(AssetFees::set_fee_asset(Origin::signed(2), Some(ASSET)));
+
+		assert_eq!(
+			FeeAdapter::withdraw_fee(&2, &remark(), &DispatchInfo::default(), INITIAL_BALANCE, 0)
+				.err(),
+			Some(InvalidTransaction::Payment.into()),
+		);
+		assert_eq!(Assets::balance(ASSET, 2), INITIAL_BALANCE);
+	});
+}
+
+#[test]
+fn fee_asset_must_be_accepted() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			AssetFees::set_fee_asset(Origin::signed(2), Some(UNRATED)),
+			Error::<Test>::NoConversionRate,
+		);
+	});
+}
+
+#[test]
+fn native_currency_is_used_once_the_asset_is_no_longer_accepted() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(AssetFees::set_fee_asset(Origin::signed(1), Some(ASSET)));
+		assert_ok!(AssetFees::set_conversion_rate(Origin::root(), ASSET, None));
+
+		charge(1, 100, 100);
+
+		assert_eq!(Balances::free_balance(1), INITIAL_BALANCE - 100);
+		assert_eq!(Assets::balance(ASSET, 1), INITIAL_BALANCE);
+	});
+}
+
+#[test]
+fn conversion_rate_is_set_by_root() {
+	new_test_ext().execute_with(|| {
+		let rate = FixedU128::saturating_from_rational(1, 2);
+		assert_noop!(AssetFees::set_conversion_rate(Origin::signed(1), UNRATED, Some(rate)), BadOrigin);
+
+		assert_ok!(AssetFees::set_conversion_rate(Origin::root(), UNRATED, Some(rate)));
+		assert_eq!(AssetFees::conversion_rate(UNRATED), Some(rate));
+		System::assert_last_event(AssetFeesEvent::ConversionRateSet(UNRATED, Some(rate)).into());
+
+		assert_ok!(AssetFees::set_fee_asset(Origin::signed(2), Some(UNRATED)));
+		charge(2, 100, 100);
+		assert_eq!(Assets::balance(UNRATED, 2), INITIAL_BALANCE - 50);
+	});
+}
+
+#[test]
+fn fees_converting_to_nothing_are_paid_in_native_currency() {
+	new_test_ext().execute_with(|| {
+		let rate = FixedU128::saturating_from_rational(1, 1_000);
+		assert_ok!(AssetFees::set_conversion_rate(Origin::root(), ASSET, Some(rate)));
+		assert_ok!(AssetFees::set_fee_asset(Origin::signed(1), Some(ASSET)));
+
+		charge(1, 100, 100);
+
+		assert_eq!(Balances::free_balance(1), INITIAL_BALANCE - 100);
+		assert_eq!(Assets::balance(ASSET, 1), INITIAL_BALANCE);
+	});
+}
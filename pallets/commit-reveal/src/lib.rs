@@ -0,0 +1,271 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+/// Randomness generated from secrets committed and revealed by bonded participants.
+///
+/// Accounts join by reserving a bond. Time is divided in rounds, made of a commit phase and a
+/// reveal phase. During the commit phase, participants commit the hash of a secret bound to
+/// their account; during the reveal phase, they reveal the secret. When the round closes, the
+/// revealed secrets are combined into the new seed, and the participants which committed
+/// without revealing are slashed their bond and removed.
+///
+/// Secrets are combined regardless of the order they are revealed in, so the block author
+/// can't bias the seed by ordering reveals. The last revealer can still choose not to reveal,
+/// which is what the bond is at stake for.
+
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use frame_support::{
+		dispatch::DispatchResult,
+		pallet_prelude::*,
+		traits::{Currency, Imbalance, OnUnbalanced, Randomness, ReservableCurrency},
+	};
+	use frame_system::pallet_prelude::*;
+	use sp_runtime::traits::{Hash, Saturating, Zero};
+	use sp_std::prelude::*;
+
+	pub(crate) type BalanceOf<T> =
+		<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+	pub(crate) type NegativeImbalanceOf<T> = <<T as Config>::Currency as Currency<
+		<T as frame_system::Config>::AccountId,
+	>>::NegativeImbalance;
+
+	/// Configure the pallet by specifying the parameters and types on which it depends.
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// Because this pallet emits events, it depends on the runtime's definition of an event.
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+		/// The currency in which bonds are reserved.
+		type Currency: ReservableCurrency<Self::AccountId>;
+		/// The bond reserved from participants.
+		#[pallet::constant]
+		type Bond: Get<BalanceOf<Self>>;
+		/// The number of blocks in which secrets are committed, at the start of each round.
+		#[pallet::constant]
+		type CommitPeriod: Get<Self::BlockNumber>;
+		/// The number of blocks in which secrets are revealed, at the end of each round.
+		#[pallet::constant]
+		type RevealPeriod: Get<Self::BlockNumber>;
+		/// The maximum number of participants.
+		#[pallet::constant]
+		type MaxParticipants: Get<u32>;
+		/// Handler for the bonds slashed from participants which didn't reveal their secret.
+		type Slash: OnUnbalanced<NegativeImbalanceOf<Self>>;
+	}
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	/// The bond reserved from each participant.
+	#[pallet::storage]
+	#[pallet::getter(fn participant)]
+	pub type Participants<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, BalanceOf<T>>;
+
+	/// The number of participants.
+	#[pallet::storage]
+	#[pallet::getter(fn participant_count)]
+	pub type ParticipantCount<T> = StorageValue<_, u32, ValueQuery>;
+
+	/// The commitments of the current round not revealed yet.
+	#[pallet::storage]
+	#[pallet::getter(fn commitment)]
+	pub type Commitments<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, T::Hash>;
+
+	/// The secrets revealed in the current round, combined.
+	#[pallet::storage]
+	pub type PendingSeed<T: Config> = StorageValue<_, T::Hash, ValueQuery>;
+
+	/// The number of secrets revealed in the current round.
+	#[pallet::storage]
+	#[pallet::getter(fn reveals)]
+	pub type Reveals<T> = StorageValue<_, u32, ValueQuery>;
+
+	/// The current seed, and the block in which it was determined.
+	#[pallet::storage]
+	#[pallet::getter(fn seed)]
+	pub type Seed<T: Config> = StorageValue<_, (T::Hash, T::BlockNumber), ValueQuery>;
+
+	#[pallet::event]
+	#[pallet::metadata(T::AccountId = "AccountId", BalanceOf<T> = "Balance", T::Hash = "Hash")]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// An account joined, reserving a bond. [who, bond]
+		Joined(T::AccountId, BalanceOf<T>),
+		/// A participant left, its bond released. [who]
+		Left(T::AccountId),
+		/// A participant committed a secret. [who, commitment]
+		Committed(T::AccountId, T::Hash),
+		/// A participant revealed its secret. [who]
+		Revealed(T::AccountId),
+		/// A participant didn't reveal its secret, and was slashed and removed. [who, amount]
+		Slashed(T::AccountId, BalanceOf<T>),
+		/// A round closed with secrets revealed, determining a new seed. [seed]
+		NewSeed(T::Hash),
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The account is a participant already.
+		AlreadyParticipant,
+		/// The account is not a participant.
+		NotParticipant,
+		/// There are `MaxParticipants` participants already.
+		TooManyParticipants,
+		/// Secrets can only be committed in the commit phase.
+		NotCommitPhase,
+		/// Secrets can only be revealed in the reveal phase.
+		NotRevealPhase,
+		/// The participant committed a secret in this round already.
+		AlreadyCommitted,
+		/// The participant has no commitment to reveal.
+		NoCommitment,
+		/// The secret doesn't match the commitment of the participant.
+		InvalidReveal,
+		/// Participants can't leave before revealing their secret.
+		CommitmentPending,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		/// Close the previous round at the start of each round.
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			if now.is_zero() || !(now % Self::round_length()).is_zero() {
+				return 0
+			}
+
+			let mut slashed: Weight = 0;
+			for (who, _) in <Commitments<T>>::drain() {
+				if let Some(bond) = <Participants<T>>::take(&who) {
+					let (imbalance, _) = T::Currency::slash_reserved(&who, bond);
+					let amount = imbalance.peek();
+					T::Slash::on_unbalanced(imbalance);
+					<ParticipantCount<T>>::mutate(|count| *count = count.saturating_sub(1));
+					Self::deposit_event(Event::Slashed(who, amount));
+				}
+				slashed += 1;
+			}
+
+			if !<Reveals<T>>::take().is_zero() {
+				let seed = T::Hashing::hash_of(&<PendingSeed<T>>::take());
+				<Seed<T>>::put((seed, now));
+				Self::deposit_event(Event::NewSeed(seed));
+			}
+
+			T::DbWeight::get().reads_writes(3 + 3 * slashed, 4 + 4 * slashed)
+				.saturating_add((5_000_000 as Weight).saturating_mul(slashed))
+		}
+
+		fn integrity_test() {
+			assert!(!T::CommitPeriod::get().is_zero(), "`CommitPeriod` must not be zero");
+			assert!(!T::RevealPeriod::get().is_zero(), "`RevealPeriod` must not be zero");
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Become a participant, reserving `Bond` from the sender.
+		#[pallet::weight(50_000_000 + T::DbWeight::get().reads_writes(3, 3))]
+		pub fn join(origin: OriginFor<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(!<Participants<T>>::contains_key(&who), Error::<T>::AlreadyParticipant);
+			let count = Self::participant_count();
+			ensure!(count < T::MaxParticipants::get(), Error::<T>::TooManyParticipants);
+
+			let bond = T::Bond::get();
+			T::Currency::reserve(&who, bond)?;
+
+			<Participants<T>>::insert(&who, bond);
+			<ParticipantCount<T>>::put(count + 1);
+
+			Self::deposit_event(Event::Joined(who, bond));
+			Ok(())
+		}
+
+		/// Stop participating, releasing the bond of the sender.
+		///
+		/// Participants which committed a secret must reveal it before leaving.
+		#[pallet::weight(50_000_000 + T::DbWeight::get().reads_writes(3, 3))]
+		pub fn leave(origin: OriginFor<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let bond = Self::participant(&who).ok_or(Error::<T>::NotParticipant)?;
+			ensure!(!<Commitments<T>>::contains_key(&who), Error::<T>::CommitmentPending);
+
+			T::Currency::unreserve(&who, bond);
+			<Participants<T>>::remove(&who);
+			<ParticipantCount<T>>::mutate(|count| *count = count.saturating_sub(1));
+
+			Self::deposit_event(Event::Left(who));
+			Ok(())
+		}
+
+		/// Commit to a secret for the current round.
+		///
+		/// `commitment` must be the `commitment` of the sender and its secret, so that other
+		/// participants can't reveal the same secret.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(2, 1))]
+		pub fn commit(origin: OriginFor<T>, commitment: T::Hash) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(<Participants<T>>::contains_key(&who), Error::<T>::NotParticipant);
+			ensure!(Self::is_commit_phase(), Error::<T>::NotCommitPhase);
+			ensure!(!<Commitments<T>>::contains_key(&who), Error::<T>::AlreadyCommitted);
+
+			<Commitments<T>>::insert(&who, commitment);
+
+			Self::deposit_event(Event::Committed(who, commitment));
+			Ok(())
+		}
+
+		/// Reveal the secret committed to in the current round.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(3, 3))]
+		pub fn reveal(origin: OriginFor<T>, secret: T::Hash) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(!Self::is_commit_phase(), Error::<T>::NotRevealPhase);
+			let commitment = Self::commitment(&who).ok_or(Error::<T>::NoCommitment)?;
+			ensure!(commitment == Self::commitment_of(&who, &secret), Error::<T>::InvalidReveal);
+
+			<Commitments<T>>::remove(&who);
+			<PendingSeed<T>>::mutate(|seed| *seed = *seed ^ secret);
+			<Reveals<T>>::mutate(|reveals| *reveals = reveals.saturating_add(1));
+
+			Self::deposit_event(Event::Revealed(who));
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// The commitment of `who` to `secret`.
+		pub fn commitment_of(who: &T::AccountId, secret: &T::Hash) -> T::Hash {
+			T::Hashing::hash_of(&(who, secret))
+		}
+
+		/// Whether the current block is in the commit phase of its round.
+		pub fn is_commit_phase() -> bool {
+			let now = <frame_system::Pallet<T>>::block_number();
+			now % Self::round_length() < T::CommitPeriod::get()
+		}
+
+		fn round_length() -> T::BlockNumber {
+			T::CommitPeriod::get().saturating_add(T::RevealPeriod::get())
+		}
+	}
+
+	/// Randomness derived from the seed of the last round with secrets revealed.
+	///
+	/// The block returned is the one in which the seed was determined: it doesn't change
+	/// before the next round closes, and was known to anyone from then on.
+	impl<T: Config> Randomness<T::Hash, T::BlockNumber> for Pallet<T> {
+		fn random(subject: &[u8]) -> (T::Hash, T::BlockNumber) {
+			let (seed, block) = Self::seed();
+			(T::Hashing::hash_of(&(subject, seed)), block)
+		}
+	}
+}
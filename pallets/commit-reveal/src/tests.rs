@@ -0,0 +1,192 @@
+use crate::{mock::*, Error, Event as CommitRevealEvent};
+use frame_support::{assert_noop, assert_ok, traits::{Hooks, Randomness, ReservableCurrency}};
+use sp_core::H256;
+
+/// Initialize the blocks up to `n`.
+fn run_to_block(n: u64) {
+	while System::block_number() < n {
+		System::set_block_number(System::block_number() + 1);
+		CommitReveal::on_initialize(System::block_number());
+	}
+}
+
+/// Join with `who` and commit to `secret`.
+fn join_and_commit(who: u64, secret: H256) {
+	assert_ok!(CommitReveal::join(Origin::signed(who)));
+	assert_ok!(CommitReveal::commit(Origin::signed(who), CommitReveal::commitment_of(&who, &secret)));
+}
+
+/// The seed of a round in which accounts 1, 2 and 3 reveal their secret, in `order`.
+fn seed_revealed_in(order: [u64; 3]) -> H256 {
+	new_test_ext().execute_with(|| {
+		for who in 1..=3 {
+			join_and_commit(who, H256::repeat_byte(who as u8));
+		}
+		run_to_block(3);
+		for who in order.iter() {
+			assert_ok!(CommitReveal::reveal(Origin::signed(*who), H256::repeat_byte(*who as u8)));
+		}
+		run_to_block(5);
+		CommitReveal::seed().0
+	})
+}
+
+/// The seed of a round in which account 1 alone reveals its secret.
+fn seed_revealed_by_1_alone() -> H256 {
+	new_test_ext().execute_with(|| {
+		join_and_commit(1, H256::repeat_byte(1));
+		run_to_block(3);
+		assert_ok!(CommitReveal::reveal(Origin::signed(1), H256::repeat_byte(1)));
+		run_to_block(5);
+		CommitReveal::seed().0
+	})
+}
+
+#[test]
+fn join_and_leave_reserve_and_release_the_bond() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(CommitReveal::join(Origin::signed(1)));
+		System::assert_last_event(CommitRevealEvent::Joined(1, 10).into());
+		assert_eq!(Balances::reserved_balance(1), 10);
+		assert_eq!(CommitReveal::participant_count(), 1);
+		assert_noop!(CommitReveal::join(Origin::signed(1)), Error::<Test>::AlreadyParticipant);
+
+		assert_ok!(CommitReveal::leave(Origin::signed(1)));
+		System::assert_last_event(CommitRevealEvent::Left(1).into());
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(CommitReveal::participant_count(), 0);
+		assert_noop!(CommitReveal::leave(Origin::signed(1)), Error::<Test>::NotParticipant);
+	});
+}
+
+#[test]
+fn participants_are_limited() {
+	new_test_ext().execute_with(|| {
+		for who in 1..=3 {
+			assert_ok!(CommitReveal::join(Origin::signed(who)));
+		}
+		assert_noop!(CommitReveal::join(Origin::signed(4)), Error::<Test>::TooManyParticipants);
+	});
+}
+
+#[test]
+fn secrets_are_committed_then_revealed() {
+	new_test_ext().execute_with(|| {
+		let secret = H256::repeat_byte(1);
+		let commitment = CommitReveal::commitment_of(&1, &secret);
+		assert_noop!(CommitReveal::commit(Origin::signed(1), commitment), Error::<Test>::NotParticipant);
+
+		join_and_commit(1, secret);
+		System::assert_last_event(CommitRevealEvent::Committed(1, commitment).into());
+		assert_noop!(CommitReveal::commit(Origin::signed(1), commitment), Error::<Test>::AlreadyCommitted);
+		assert_noop!(CommitReveal::reveal(Origin::signed(1), secret), Error::<Test>::NotRevealPhase);
+		assert_noop!(CommitReveal::leave(Origin::signed(1)), Error::<Test>::CommitmentPending);
+
+		run_to_block(3);
+		assert_ok!(CommitReveal::join(Origin::signed(2)));
+		assert_noop!(
+			CommitReveal::commit(Origin::signed(2), CommitReveal::commitment_of(&2, &secret)),
+			Error::<Test>::NotCommitPhase,
+		);
+		assert_ok!(CommitReveal::reveal(Origin::signed(1), secret));
+		System::assert_last_event(CommitRevealEvent::Revealed(1).into());
+		assert_noop!(CommitReveal::reveal(Origin::signed(1), secret), Error::<Test>::NoCommitment);
+
+		run_to_block(5);
+		let seed = CommitReveal::seed();
+		assert_ne!(seed.0, H256::zero());
+		assert_eq!(seed.1, 5);
+		System::assert_last_event(CommitRevealEvent::NewSeed(seed.0).into());
+		// The secret was revealed, so the bond is kept.
+		assert_eq!(Balances::reserved_balance(1), 10);
+	});
+}
+
+#[test]
+fn committed_secrets_cant_be_changed() {
+	new_test_ext().execute_with(|| {
+		join_and_commit(1, H256::repeat_byte(1));
+		run_to_block(3);
+
+		assert_noop!(
+			CommitReveal::reveal(Origin::signed(1), H256::repeat_byte(2)),
+			Error::<Test>::InvalidReveal,
+		);
+	});
+}
+
+#[test]
+fn commitments_cant_be_copied() {
+	new_test_ext().execute_with(|| {
+		let secret = H256::repeat_byte(1);
+		join_and_commit(1, secret);
+		// Revealing the secret of 1 a second time would cancel it out of the seed.
+		assert_ok!(CommitReveal::join(Origin::signed(2)));
+		assert_ok!(CommitReveal::commit(Origin::signed(2), CommitReveal::commitment_of(&1, &secret)));
+		run_to_block(3);
+
+		assert_ok!(CommitReveal::reveal(Origin::signed(1), secret));
+		assert_noop!(CommitReveal::reveal(Origin::signed(2), secret), Error::<Test>::InvalidReveal);
+	});
+}
+
+#[test]
+fn reveal_order_does_not_change_the_seed() {
+	let seed = seed_revealed_in([1, 2, 3]);
+	assert_eq!(seed_revealed_in([3, 1, 2]), seed);
+	assert_eq!(seed_revealed_in([2, 3, 1]), seed);
+}
+
+#[test]
+fn withholding_a_secret_is_slashed() {
+	let expected = seed_revealed_by_1_alone();
+	new_test_ext().execute_with(|| {
+		join_and_commit(1, H256::repeat_byte(1));
+		join_and_commit(2, H256::repeat_byte(2));
+		let issuance = Balances::total_issuance();
+		run_to_block(3);
+		assert_ok!(CommitReveal::reveal(Origin::signed(1), H256::repeat_byte(1)));
+
+		run_to_block(5);
+		let slashed: Event = CommitRevealEvent::Slashed(2, 10).into();
+		assert!(System::events().iter().any(|record| record.event == slashed));
+		assert_eq!(CommitReveal::participant(2), None);
+		assert_eq!(CommitReveal::participant_count(), 1);
+		assert_eq!(Balances::reserved_balance(2), 0);
+		assert_eq!(Balances::free_balance(2), INITIAL_BALANCE - 10);
+		assert_eq!(Balances::total_issuance(), issuance - 10);
+		// Only the revealed secret makes the seed.
+		assert_eq!(CommitReveal::seed().0, expected);
+	});
+}
+
+#[test]
+fn seed_is_kept_without_reveals() {
+	new_test_ext().execute_with(|| {
+		join_and_commit(1, H256::repeat_byte(1));
+		run_to_block(3);
+		assert_ok!(CommitReveal::reveal(Origin::signed(1), H256::repeat_byte(1)));
+		run_to_block(5);
+		let seed = CommitReveal::seed();
+		assert_eq!(seed.1, 5);
+
+		run_to_block(10);
+		assert_eq!(CommitReveal::seed(), seed);
+		assert_eq!(CommitReveal::random_seed(), CommitReveal::random(&[][..]));
+		assert_eq!(CommitReveal::random(b"subject").1, 5);
+		assert_ne!(CommitReveal::random(b"subject").0, CommitReveal::random(b"other").0);
+	});
+}
+
+#[test]
+fn slashed_bonds_are_what_is_left_reserved() {
+	new_test_ext().execute_with(|| {
+		join_and_commit(1, H256::repeat_byte(1));
+		// Some of the bond is slashed by another pallet.
+		let _ = Balances::slash_reserved(&1, 4);
+
+		run_to_block(5);
+		System::assert_last_event(CommitRevealEvent::Slashed(1, 6).into());
+		assert_eq!(Balances::free_balance(1), INITIAL_BALANCE - 10);
+	});
+}
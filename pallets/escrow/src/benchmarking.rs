@@ -0,0 +1,82 @@
+//! Benchmarking setup for pallet-escrow
+
+use super::*;
+
+use frame_benchmarking::{account, benchmarks, whitelisted_caller, impl_benchmark_test_suite};
+use frame_support::traits::{Currency, Get, Hooks};
+use frame_system::RawOrigin;
+use sp_runtime::traits::{Bounded, Saturating, StaticLookup};
+#[allow(unused)]
+use crate::Pallet as Escrow;
+
+/// The furthest block escrows created now can expire in.
+fn expiry<T: Config>() -> T::BlockNumber {
+	frame_system::Pallet::<T>::block_number().saturating_add(T::MaxExpiry::get())
+}
+
+/// Put `count` payments of `MinAmount` in escrow from a funded caller, all expiring in block
+/// `expiry`.
+fn create_escrows<T: Config>(count: u32, expiry: T::BlockNumber) -> T::AccountId {
+	let caller: T::AccountId = whitelisted_caller();
+	T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+	let payee = T::Lookup::unlookup(account("payee", 0, 0));
+	let arbiter = T::Lookup::unlookup(account("arbiter", 0, 0));
+	for _ in 0 .. count {
+		Escrow::<T>::create(
+			RawOrigin::Signed(caller.clone()).into(),
+			payee.clone(),
+			arbiter.clone(),
+			T::MinAmount::get(),
+			expiry,
+		).unwrap();
+	}
+	caller
+}
+
+benchmarks! {
+	create {
+		let max = T::MaxExpiringPerBlock::get();
+		let expiry = expiry::<T>();
+		// The escrow takes the last place left in the block of its expiry.
+		let caller = create_escrows::<T>(max.saturating_sub(1), expiry);
+		let payee = T::Lookup::unlookup(account("payee", 0, 0));
+		let arbiter = T::Lookup::unlookup(account("arbiter", 0, 0));
+	}: _(RawOrigin::Signed(caller), payee, arbiter, T::MinAmount::get(), expiry)
+	verify {
+		assert_eq!(Escrow::<T>::expiring(expiry).len() as u32, max);
+	}
+
+	release {
+		// Settling an escrow removes it from the full block of its expiry.
+		let caller = create_escrows::<T>(T::MaxExpiringPerBlock::get(), expiry::<T>());
+	}: _(RawOrigin::Signed(caller), 0)
+	verify {
+		assert!(Escrow::<T>::escrow(0).is_none());
+	}
+
+	refund {
+		create_escrows::<T>(T::MaxExpiringPerBlock::get(), expiry::<T>());
+		let payee: T::AccountId = account("payee", 0, 0);
+	}: _(RawOrigin::Signed(payee), 0)
+	verify {
+		assert!(Escrow::<T>::escrow(0).is_none());
+	}
+
+	on_initialize {
+		let n in 0 .. T::MaxExpiringPerBlock::get();
+		let expiry = expiry::<T>();
+		create_escrows::<T>(n, expiry);
+	}: {
+		Escrow::<T>::on_initialize(expiry);
+	}
+	verify {
+		assert!(Escrow::<T>::expiring(expiry).is_empty());
+		assert_eq!(Escrow::<T>::escrow(0), None);
+	}
+}
+
+impl_benchmark_test_suite!(
+	Escrow,
+	crate::mock::new_test_ext(),
+	crate::mock::Test,
+);
@@ -0,0 +1,261 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+/// Payments held in escrow between a payer, a payee and an arbiter.
+///
+/// The payer reserves the amount of the payment, at least `MinAmount`, when creating the escrow.
+/// Each party can only give up its own claim: the payer releases the payment to the payee, the
+/// payee refunds it to the payer, and the arbiter can do either. Escrows left unsettled are
+/// refunded to the payer at their expiry block, at most `MaxExpiry` blocks after their creation.
+
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+
+pub mod weights;
+pub use weights::WeightInfo;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use frame_support::{
+		dispatch::DispatchResult,
+		pallet_prelude::*,
+		traits::{BalanceStatus, Currency, ReservableCurrency},
+	};
+	use frame_system::pallet_prelude::*;
+	use sp_runtime::traits::{Saturating, StaticLookup};
+	use sp_std::prelude::*;
+	use crate::WeightInfo;
+
+	pub(crate) type BalanceOf<T> =
+		<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+	/// Identifier of an escrow.
+	pub type EscrowId = u32;
+
+	/// A payment held in escrow.
+	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug)]
+	pub struct EscrowDetails<AccountId, Balance, BlockNumber> {
+		/// The account which reserved `amount`.
+		pub payer: AccountId,
+		/// The account `amount` is released to.
+		pub payee: AccountId,
+		/// The account settling disputes between the payer and the payee.
+		pub arbiter: AccountId,
+		/// The amount of the payment.
+		pub amount: Balance,
+		/// The block in which the payment is refunded, unless settled before.
+		pub expiry: BlockNumber,
+	}
+
+	pub(crate) type EscrowOf<T> = EscrowDetails<
+		<T as frame_system::Config>::AccountId,
+		BalanceOf<T>,
+		<T as frame_system::Config>::BlockNumber,
+	>;
+
+	/// Configure the pallet by specifying the parameters and types on which it depends.
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// Because this pallet emits events, it depends on the runtime's definition of an event.
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+		/// The currency in which payments are made.
+		type Currency: ReservableCurrency<Self::AccountId>;
+		/// The minimum amount of a payment in escrow.
+		#[pallet::constant]
+		type MinAmount: Get<BalanceOf<Self>>;
+		/// The maximum number of blocks before an escrow expires.
+		#[pallet::constant]
+		type MaxExpiry: Get<Self::BlockNumber>;
+		/// The maximum number of escrows expiring in the same block.
+		#[pallet::constant]
+		type MaxExpiringPerBlock: Get<u32>;
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	/// The identifier of the next escrow created.
+	#[pallet::storage]
+	#[pallet::getter(fn next_escrow_id)]
+	pub type NextEscrowId<T> = StorageValue<_, EscrowId, ValueQuery>;
+
+	/// The escrows not settled yet.
+	#[pallet::storage]
+	#[pallet::getter(fn escrow)]
+	pub type Escrows<T: Config> = StorageMap<_, Twox64Concat, EscrowId, EscrowOf<T>>;
+
+	/// The escrows expiring in each block.
+	#[pallet::storage]
+	#[pallet::getter(fn expiring)]
+	pub type Expiring<T: Config> =
+		StorageMap<_, Twox64Concat, T::BlockNumber, Vec<EscrowId>, ValueQuery>;
+
+	#[pallet::event]
+	#[pallet::metadata(
+		T::AccountId = "AccountId",
+		BalanceOf<T> = "Balance",
+		T::BlockNumber = "BlockNumber"
+	)]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// A payment was put in escrow. [escrow, payer, payee, arbiter, amount, expiry]
+		Created(EscrowId, T::AccountId, T::AccountId, T::AccountId, BalanceOf<T>, T::BlockNumber),
+		/// A payment was released to the payee. [escrow, payee, amount]
+		Released(EscrowId, T::AccountId, BalanceOf<T>),
+		/// A payment was refunded to the payer. [escrow, payer, amount]
+		Refunded(EscrowId, T::AccountId, BalanceOf<T>),
+		/// A payment expired and was refunded to the payer. [escrow, payer, amount]
+		Expired(EscrowId, T::AccountId, BalanceOf<T>),
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The escrow does not exist, or was settled already.
+		UnknownEscrow,
+		/// Only the payer or the arbiter can release a payment.
+		NotPayerOrArbiter,
+		/// Only the payee or the arbiter can refund a payment.
+		NotPayeeOrArbiter,
+		/// The payment is below `MinAmount`.
+		AmountTooLow,
+		/// The expiry block must be in the future.
+		ExpiryInPast,
+		/// The expiry block is more than `MaxExpiry` blocks away.
+		ExpiryTooFar,
+		/// Too many escrows expire in the same block already.
+		TooManyExpiring,
+		/// No more escrows can be created.
+		NoAvailableEscrowId,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		/// Refund the escrows expiring in this block.
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			let expiring = <Expiring<T>>::take(now);
+			let count = expiring.len() as u32;
+
+			for id in expiring {
+				if let Some(escrow) = <Escrows<T>>::take(id) {
+					let refunded = Self::refund_to_payer(&escrow);
+					Self::deposit_event(Event::Expired(id, escrow.payer, refunded));
+				}
+			}
+
+			T::WeightInfo::on_initialize(count)
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Put `amount` in escrow for `payee`, reserving it from the sender.
+		///
+		/// Unless settled before, the payment is refunded to the sender in block `expiry`, at most
+		/// `MaxExpiry` blocks away.
+		#[pallet::weight(T::WeightInfo::create())]
+		pub fn create(
+			origin: OriginFor<T>,
+			payee: <T::Lookup as StaticLookup>::Source,
+			arbiter: <T::Lookup as StaticLookup>::Source,
+			#[pallet::compact] amount: BalanceOf<T>,
+			expiry: T::BlockNumber,
+		) -> DispatchResult {
+			let payer = ensure_signed(origin)?;
+			let payee = T::Lookup::lookup(payee)?;
+			let arbiter = T::Lookup::lookup(arbiter)?;
+			ensure!(amount >= T::MinAmount::get(), Error::<T>::AmountTooLow);
+			let now = <frame_system::Pallet<T>>::block_number();
+			ensure!(expiry > now, Error::<T>::ExpiryInPast);
+			ensure!(expiry <= now.saturating_add(T::MaxExpiry::get()), Error::<T>::ExpiryTooFar);
+
+			let id = Self::next_escrow_id();
+			let next = id.checked_add(1).ok_or(Error::<T>::NoAvailableEscrowId)?;
+			let mut expiring = Self::expiring(expiry);
+			ensure!(
+				(expiring.len() as u32) < T::MaxExpiringPerBlock::get(),
+				Error::<T>::TooManyExpiring,
+			);
+
+			T::Currency::reserve(&payer, amount)?;
+
+			expiring.push(id);
+			<Expiring<T>>::insert(expiry, expiring);
+			<NextEscrowId<T>>::put(next);
+			<Escrows<T>>::insert(id, EscrowDetails {
+				payer: payer.clone(),
+				payee: payee.clone(),
+				arbiter: arbiter.clone(),
+				amount,
+				expiry,
+			});
+
+			Self::deposit_event(Event::Created(id, payer, payee, arbiter, amount, expiry));
+			Ok(())
+		}
+
+		/// Release the payment in `escrow` to its payee.
+		///
+		/// The sender must be the payer or the arbiter of the escrow.
+		#[pallet::weight(T::WeightInfo::release())]
+		pub fn release(origin: OriginFor<T>, #[pallet::compact] escrow: EscrowId) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let details = Self::escrow(escrow).ok_or(Error::<T>::UnknownEscrow)?;
+			ensure!(who == details.payer || who == details.arbiter, Error::<T>::NotPayerOrArbiter);
+
+			// The payer's reserve may have been slashed since, only what is left is released.
+			let remaining = T::Currency::repatriate_reserved(
+				&details.payer,
+				&details.payee,
+				details.amount,
+				BalanceStatus::Free,
+			)?;
+			let released = details.amount.saturating_sub(remaining);
+			Self::settle(escrow, &details);
+
+			Self::deposit_event(Event::Released(escrow, details.payee, released));
+			Ok(())
+		}
+
+		/// Refund the payment in `escrow` to its payer.
+		///
+		/// The sender must be the payee or the arbiter of the escrow.
+		#[pallet::weight(T::WeightInfo::refund())]
+		pub fn refund(origin: OriginFor<T>, #[pallet::compact] escrow: EscrowId) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let details = Self::escrow(escrow).ok_or(Error::<T>::UnknownEscrow)?;
+			ensure!(who == details.payee || who == details.arbiter, Error::<T>::NotPayeeOrArbiter);
+			Self::settle(escrow, &details);
+
+			let refunded = Self::refund_to_payer(&details);
+
+			Self::deposit_event(Event::Refunded(escrow, details.payer, refunded));
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// Remove a settled escrow, so that it doesn't expire.
+		fn settle(id: EscrowId, details: &EscrowOf<T>) {
+			<Escrows<T>>::remove(id);
+			<Expiring<T>>::mutate(details.expiry, |expiring| expiring.retain(|other| *other != id));
+		}
+
+		/// Unreserve the payment of an escrow, returning the amount refunded.
+		fn refund_to_payer(details: &EscrowOf<T>) -> BalanceOf<T> {
+			let remaining = T::Currency::unreserve(&details.payer, details.amount);
+			details.amount.saturating_sub(remaining)
+		}
+	}
+}
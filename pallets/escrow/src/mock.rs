@@ -0,0 +1,105 @@
+use crate as pallet_escrow;
+use sp_core::H256;
+use frame_support::parameter_types;
+use sp_runtime::{
+	traits::{BlakeTwo256, IdentityLookup}, testing::Header,
+};
+use frame_system as system;
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+// Configure a mock runtime to test the pallet.
+frame_support::construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+		Escrow: pallet_escrow::{Pallet, Call, Storage, Event<T>},
+	}
+);
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const SS58Prefix: u8 = 42;
+}
+
+impl system::Config for Test {
+	type BaseCallFilter = ();
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type Origin = Origin;
+	type Call = Call;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = pallet_balances::AccountData<u64>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = SS58Prefix;
+	type OnSetCode = ();
+}
+
+parameter_types! {
+	pub const ExistentialDeposit: u64 = 1;
+}
+
+impl pallet_balances::Config for Test {
+	type MaxLocks = ();
+	type Balance = u64;
+	type Event = Event;
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub const MinAmount: u64 = 5;
+	pub const MaxExpiry: u64 = 20;
+	pub const MaxExpiringPerBlock: u32 = 2;
+}
+
+impl pallet_escrow::Config for Test {
+	type Event = Event;
+	type Currency = Balances;
+	type MinAmount = MinAmount;
+	type MaxExpiry = MaxExpiry;
+	type MaxExpiringPerBlock = MaxExpiringPerBlock;
+	type WeightInfo = ();
+}
+
+/// The payer of escrows in tests.
+pub const PAYER: u64 = 1;
+/// The payee of escrows in tests.
+pub const PAYEE: u64 = 2;
+/// The arbiter of escrows in tests.
+pub const ARBITER: u64 = 3;
+/// The free balance of `PAYER`, `PAYEE` and `ARBITER` at genesis.
+pub const INITIAL_BALANCE: u64 = 100;
+
+// Build genesis storage according to the mock runtime.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut t = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	pallet_balances::GenesisConfig::<Test> {
+		balances: vec![(PAYER, INITIAL_BALANCE), (PAYEE, INITIAL_BALANCE), (ARBITER, INITIAL_BALANCE)],
+	}.assimilate_storage(&mut t).unwrap();
+
+	let mut ext = sp_io::TestExternalities::new(t);
+	// Events are not deposited at genesis.
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
@@ -0,0 +1,146 @@
+use crate::{mock::*, EscrowDetails, Error, Event as EscrowEvent};
+use frame_support::{assert_noop, assert_ok, traits::{Get, Hooks, ReservableCurrency}};
+
+/// Put 40 in escrow from `PAYER` to `PAYEE`, expiring in block 10.
+fn create() {
+	assert_ok!(Escrow::create(Origin::signed(PAYER), PAYEE, ARBITER, 40, 10));
+}
+
+#[test]
+fn create_reserves_the_payment() {
+	new_test_ext().execute_with(|| {
+		create();
+		System::assert_last_event(EscrowEvent::Created(0, PAYER, PAYEE, ARBITER, 40, 10).into());
+
+		assert_eq!(Escrow::escrow(0), Some(EscrowDetails {
+			payer: PAYER,
+			payee: PAYEE,
+			arbiter: ARBITER,
+			amount: 40,
+			expiry: 10,
+		}));
+		assert_eq!(Escrow::expiring(10), vec![0]);
+		assert_eq!(Escrow::next_escrow_id(), 1);
+		assert_eq!(Balances::reserved_balance(PAYER), 40);
+		assert_eq!(Balances::free_balance(PAYER), INITIAL_BALANCE - 40);
+	});
+}
+
+#[test]
+fn create_checks_its_arguments() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Escrow::create(Origin::signed(PAYER), PAYEE, ARBITER, MinAmount::get() - 1, 10),
+			Error::<Test>::AmountTooLow,
+		);
+		assert_noop!(
+			Escrow::create(Origin::signed(PAYER), PAYEE, ARBITER, 40, 1),
+			Error::<Test>::ExpiryInPast,
+		);
+		assert_noop!(
+			Escrow::create(Origin::signed(PAYER), PAYEE, ARBITER, 40, 2 + MaxExpiry::get()),
+			Error::<Test>::ExpiryTooFar,
+		);
+		assert_noop!(
+			Escrow::create(Origin::signed(PAYER), PAYEE, ARBITER, INITIAL_BALANCE + 1, 10),
+			pallet_balances::Error::<Test>::InsufficientBalance,
+		);
+	});
+}
+
+#[test]
+fn escrows_expire_at_most_max_expiry_blocks_away() {
+	new_test_ext().execute_with(|| {
+		let expiry = 1 + MaxExpiry::get();
+		assert_ok!(Escrow::create(Origin::signed(PAYER), PAYEE, ARBITER, MinAmount::get(), expiry));
+		assert_eq!(Escrow::expiring(expiry), vec![0]);
+	});
+}
+
+#[test]
+fn expiries_are_limited_per_block() {
+	new_test_ext().execute_with(|| {
+		create();
+		create();
+
+		assert_noop!(
+			Escrow::create(Origin::signed(PAYER), PAYEE, ARBITER, 10, 10),
+			Error::<Test>::TooManyExpiring,
+		);
+		assert_ok!(Escrow::create(Origin::signed(PAYER), PAYEE, ARBITER, 10, 11));
+	});
+}
+
+#[test]
+fn payer_or_arbiter_releases() {
+	new_test_ext().execute_with(|| {
+		create();
+		create();
+		assert_noop!(Escrow::release(Origin::signed(PAYEE), 0), Error::<Test>::NotPayerOrArbiter);
+
+		assert_ok!(Escrow::release(Origin::signed(PAYER), 0));
+		System::assert_last_event(EscrowEvent::Released(0, PAYEE, 40).into());
+		assert_ok!(Escrow::release(Origin::signed(ARBITER), 1));
+		System::assert_last_event(EscrowEvent::Released(1, PAYEE, 40).into());
+
+		assert_eq!(Balances::reserved_balance(PAYER), 0);
+		assert_eq!(Balances::free_balance(PAYER), INITIAL_BALANCE - 80);
+		assert_eq!(Balances::free_balance(PAYEE), INITIAL_BALANCE + 80);
+		assert_eq!(Escrow::escrow(0), None);
+		assert!(Escrow::expiring(10).is_empty());
+		assert_noop!(Escrow::release(Origin::signed(PAYER), 0), Error::<Test>::UnknownEscrow);
+	});
+}
+
+#[test]
+fn payee_or_arbiter_refunds() {
+	new_test_ext().execute_with(|| {
+		create();
+		create();
+		assert_noop!(Escrow::refund(Origin::signed(PAYER), 0), Error::<Test>::NotPayeeOrArbiter);
+
+		assert_ok!(Escrow::refund(Origin::signed(PAYEE), 0));
+		System::assert_last_event(EscrowEvent::Refunded(0, PAYER, 40).into());
+		assert_ok!(Escrow::refund(Origin::signed(ARBITER), 1));
+		System::assert_last_event(EscrowEvent::Refunded(1, PAYER, 40).into());
+
+		assert_eq!(Balances::reserved_balance(PAYER), 0);
+		assert_eq!(Balances::free_balance(PAYER), INITIAL_BALANCE);
+		assert_eq!(Balances::free_balance(PAYEE), INITIAL_BALANCE);
+		assert!(Escrow::expiring(10).is_empty());
+		assert_noop!(Escrow::refund(Origin::signed(PAYEE), 1), Error::<Test>::UnknownEscrow);
+	});
+}
+
+#[test]
+fn unsettled_escrows_are_refunded_on_expiry() {
+	new_test_ext().execute_with(|| {
+		create();
+		assert_ok!(Escrow::create(Origin::signed(PAYER), PAYEE, ARBITER, 20, 10));
+		assert_ok!(Escrow::release(Origin::signed(PAYER), 1));
+
+		Escrow::on_initialize(9);
+		assert_eq!(Balances::reserved_balance(PAYER), 40);
+
+		Escrow::on_initialize(10);
+		System::assert_last_event(EscrowEvent::Expired(0, PAYER, 40).into());
+
+		assert_eq!(Escrow::escrow(0), None);
+		assert!(Escrow::expiring(10).is_empty());
+		assert_eq!(Balances::reserved_balance(PAYER), 0);
+		assert_eq!(Balances::free_balance(PAYER), INITIAL_BALANCE - 20);
+	});
+}
+
+#[test]
+fn only_what_is_left_of_the_payment_is_released() {
+	new_test_ext().execute_with(|| {
+		create();
+		// Another pallet slashes part of the payer's reserve.
+		let _ = Balances::slash_reserved(&PAYER, 15);
+
+		assert_ok!(Escrow::release(Origin::signed(PAYER), 0));
+		System::assert_last_event(EscrowEvent::Released(0, PAYEE, 25).into());
+		assert_eq!(Balances::free_balance(PAYEE), INITIAL_BALANCE + 25);
+	});
+}
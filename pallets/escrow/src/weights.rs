@@ -0,0 +1,47 @@
+//! Weights for pallet_escrow.
+//!
+//! NOT YET GENERATED: there are no benchmarked weights yet. The estimates of the `()`
+//! implementation, which the runtime uses in the meantime, are deliberately high, as
+//! `on_initialize` is charged to blocks, and only count the storage accesses from the code.
+//! `./scripts/benchmark.sh escrow` run on reference hardware replaces this file with measured
+//! `SubstrateWeight`s, which the runtime should then use.
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+
+/// Weight functions needed for pallet_escrow.
+pub trait WeightInfo {
+	fn create() -> Weight;
+	fn release() -> Weight;
+	fn refund() -> Weight;
+	fn on_initialize(n: u32, ) -> Weight;
+}
+
+/// Estimated weights for pallet_escrow, until they are benchmarked.
+impl WeightInfo for () {
+	fn create() -> Weight {
+		(50_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
+	}
+	fn release() -> Weight {
+		(50_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
+	}
+	fn refund() -> Weight {
+		(50_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+	fn on_initialize(n: u32, ) -> Weight {
+		(10_000_000 as Weight)
+			.saturating_add((50_000_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((2 as Weight).saturating_mul(n as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((3 as Weight).saturating_mul(n as Weight)))
+	}
+}
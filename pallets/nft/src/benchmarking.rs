@@ -0,0 +1,99 @@
+//! Benchmarking setup for pallet-nft
+
+use super::*;
+
+use frame_benchmarking::{account, benchmarks, whitelisted_caller, impl_benchmark_test_suite};
+use frame_support::traits::{Currency, Get};
+use frame_system::RawOrigin;
+use sp_runtime::traits::{Bounded, StaticLookup};
+use sp_std::prelude::*;
+#[allow(unused)]
+use crate::Pallet as Nft;
+
+/// Create a collection owned by a funded caller, with item `0` minted to the caller.
+fn create_item<T: Config>() -> T::AccountId {
+	let caller: T::AccountId = whitelisted_caller();
+	T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+	let lookup = T::Lookup::unlookup(caller.clone());
+	Nft::<T>::create_collection(RawOrigin::Signed(caller.clone()).into()).unwrap();
+	Nft::<T>::mint(RawOrigin::Signed(caller.clone()).into(), 0, 0, lookup).unwrap();
+	caller
+}
+
+/// Attach metadata of the maximum length to item `0`.
+fn set_metadata<T: Config>(caller: &T::AccountId) {
+	let data = vec![0u8; T::StringLimit::get() as usize];
+	Nft::<T>::set_metadata(RawOrigin::Signed(caller.clone()).into(), 0, 0, data).unwrap();
+}
+
+benchmarks! {
+	create_collection {
+		let caller: T::AccountId = whitelisted_caller();
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+	}: _(RawOrigin::Signed(caller.clone()))
+	verify {
+		assert_eq!(Nft::<T>::collection(0).map(|details| details.owner), Some(caller));
+	}
+
+	destroy_collection {
+		let caller: T::AccountId = whitelisted_caller();
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+		Nft::<T>::create_collection(RawOrigin::Signed(caller.clone()).into())?;
+	}: _(RawOrigin::Signed(caller), 0)
+	verify {
+		assert!(Nft::<T>::collection(0).is_none());
+	}
+
+	mint {
+		let caller: T::AccountId = whitelisted_caller();
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+		Nft::<T>::create_collection(RawOrigin::Signed(caller.clone()).into())?;
+		let owner: T::AccountId = account("owner", 0, 0);
+		let lookup = T::Lookup::unlookup(owner.clone());
+	}: _(RawOrigin::Signed(caller), 0, 0, lookup)
+	verify {
+		assert_eq!(Nft::<T>::owner_of(0, 0), Some(owner));
+	}
+
+	transfer {
+		let caller = create_item::<T>();
+		let dest: T::AccountId = account("dest", 0, 0);
+		let lookup = T::Lookup::unlookup(dest.clone());
+	}: _(RawOrigin::Signed(caller), 0, 0, lookup)
+	verify {
+		assert_eq!(Nft::<T>::owner_of(0, 0), Some(dest));
+	}
+
+	burn {
+		let caller = create_item::<T>();
+		set_metadata::<T>(&caller);
+	}: _(RawOrigin::Signed(caller), 0, 0)
+	verify {
+		assert_eq!(Nft::<T>::owner_of(0, 0), None);
+	}
+
+	set_metadata {
+		let s in 0 .. T::StringLimit::get();
+		let caller = create_item::<T>();
+		// Replacing metadata also adjusts the previous deposit.
+		set_metadata::<T>(&caller);
+		let data = vec![0u8; s as usize];
+	}: _(RawOrigin::Signed(caller), 0, 0, data.clone())
+	verify {
+		assert_eq!(Nft::<T>::metadata(0, 0).map(|metadata| metadata.data), Some(data));
+	}
+
+	clear_metadata {
+		let caller = create_item::<T>();
+		set_metadata::<T>(&caller);
+	}: _(RawOrigin::Signed(caller), 0, 0)
+	verify {
+		assert!(Nft::<T>::metadata(0, 0).is_none());
+	}
+}
+
+impl_benchmark_test_suite!(
+	Nft,
+	crate::mock::new_test_ext(),
+	crate::mock::Test,
+);
@@ -0,0 +1,403 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+/// Collections of non-fungible items.
+///
+/// Anyone can create a collection, whose owner then mints items into it and attaches metadata
+/// to them. Items can be transferred and burned by their owner. Collections, items and metadata
+/// are paid for with deposits, reserved from the collection owner until the collection is
+/// destroyed, the item burned or the metadata cleared.
+
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+
+pub mod weights;
+pub use weights::WeightInfo;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use frame_support::{
+		dispatch::DispatchResult,
+		pallet_prelude::*,
+		traits::{Currency, ReservableCurrency},
+	};
+	use frame_system::pallet_prelude::*;
+	use sp_runtime::traits::{Saturating, StaticLookup};
+	use sp_std::prelude::*;
+	use crate::WeightInfo;
+
+	pub(crate) type BalanceOf<T> =
+		<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+	/// Identifier of a collection.
+	pub type CollectionId = u32;
+	/// Identifier of an item, unique within its collection.
+	pub type ItemId = u32;
+
+	/// A collection of items.
+	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug)]
+	pub struct CollectionDetails<AccountId, Balance> {
+		/// The account allowed to mint items and to set their metadata.
+		pub owner: AccountId,
+		/// The amount reserved from `owner` for the collection.
+		pub deposit: Balance,
+		/// The number of items in the collection.
+		pub items: u32,
+	}
+
+	/// An item of a collection.
+	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug)]
+	pub struct ItemDetails<AccountId, Balance> {
+		/// The account holding the item.
+		pub owner: AccountId,
+		/// The amount reserved from the owner of the collection when the item was minted.
+		pub deposit: Balance,
+	}
+
+	/// The metadata of an item, and the deposit paid for it.
+	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug)]
+	pub struct ItemMetadata<AccountId, Balance> {
+		/// The account which reserved `deposit`.
+		pub depositor: AccountId,
+		/// The amount reserved for the metadata.
+		pub deposit: Balance,
+		/// The metadata itself, typically a URI.
+		pub data: Vec<u8>,
+	}
+
+	/// Configure the pallet by specifying the parameters and types on which it depends.
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// Because this pallet emits events, it depends on the runtime's definition of an event.
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+		/// The currency in which deposits are reserved.
+		type Currency: ReservableCurrency<Self::AccountId>;
+		/// The deposit reserved for a collection.
+		#[pallet::constant]
+		type CollectionDeposit: Get<BalanceOf<Self>>;
+		/// The deposit reserved for each item of a collection.
+		#[pallet::constant]
+		type ItemDeposit: Get<BalanceOf<Self>>;
+		/// The deposit reserved for the metadata of an item, whatever its length.
+		#[pallet::constant]
+		type MetadataDepositBase: Get<BalanceOf<Self>>;
+		/// The deposit reserved per byte of metadata.
+		#[pallet::constant]
+		type MetadataDepositPerByte: Get<BalanceOf<Self>>;
+		/// The maximum length of the metadata of an item.
+		#[pallet::constant]
+		type StringLimit: Get<u32>;
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	/// The identifier of the next collection created.
+	#[pallet::storage]
+	#[pallet::getter(fn next_collection_id)]
+	pub type NextCollectionId<T> = StorageValue<_, CollectionId, ValueQuery>;
+
+	/// The details of each collection.
+	#[pallet::storage]
+	#[pallet::getter(fn collection)]
+	pub type Collections<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		CollectionId,
+		CollectionDetails<T::AccountId, BalanceOf<T>>,
+	>;
+
+	/// The details of each item.
+	#[pallet::storage]
+	#[pallet::getter(fn item)]
+	pub type Items<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		CollectionId,
+		Twox64Concat,
+		ItemId,
+		ItemDetails<T::AccountId, BalanceOf<T>>,
+	>;
+
+	/// The items held by each account.
+	#[pallet::storage]
+	#[pallet::getter(fn owned)]
+	pub type Owned<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Twox64Concat,
+		(CollectionId, ItemId),
+		(),
+		OptionQuery,
+	>;
+
+	/// The metadata of items.
+	#[pallet::storage]
+	#[pallet::getter(fn metadata)]
+	pub type Metadata<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		CollectionId,
+		Twox64Concat,
+		ItemId,
+		ItemMetadata<T::AccountId, BalanceOf<T>>,
+	>;
+
+	#[pallet::event]
+	#[pallet::metadata(T::AccountId = "AccountId")]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// A collection was created. [collection, owner]
+		CollectionCreated(CollectionId, T::AccountId),
+		/// A collection was destroyed. [collection]
+		CollectionDestroyed(CollectionId),
+		/// An item was minted. [collection, item, owner]
+		Minted(CollectionId, ItemId, T::AccountId),
+		/// An item was transferred. [collection, item, from, to]
+		Transferred(CollectionId, ItemId, T::AccountId, T::AccountId),
+		/// An item was burned. [collection, item, owner]
+		Burned(CollectionId, ItemId, T::AccountId),
+		/// The metadata of an item was set. [collection, item]
+		MetadataSet(CollectionId, ItemId),
+		/// The metadata of an item was cleared. [collection, item]
+		MetadataCleared(CollectionId, ItemId),
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The collection does not exist.
+		UnknownCollection,
+		/// The item does not exist.
+		UnknownItem,
+		/// The item was already minted.
+		AlreadyExists,
+		/// Only the owner of the collection can do this.
+		NotCollectionOwner,
+		/// Only the owner of the item can do this.
+		NotItemOwner,
+		/// The metadata is longer than `StringLimit`.
+		MetadataTooLong,
+		/// The item has no metadata.
+		NoMetadata,
+		/// No more collections can be created.
+		NoAvailableCollectionId,
+		/// The collection still has items.
+		CollectionNotEmpty,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Create a new collection, owned by the sender, reserving `CollectionDeposit` from the
+		/// sender.
+		#[pallet::weight(T::WeightInfo::create_collection())]
+		pub fn create_collection(origin: OriginFor<T>) -> DispatchResult {
+			let owner = ensure_signed(origin)?;
+
+			let collection = Self::next_collection_id();
+			let next = collection.checked_add(1).ok_or(Error::<T>::NoAvailableCollectionId)?;
+			let deposit = T::CollectionDeposit::get();
+			T::Currency::reserve(&owner, deposit)?;
+
+			<NextCollectionId<T>>::put(next);
+			let details = CollectionDetails { owner: owner.clone(), deposit, items: 0 };
+			<Collections<T>>::insert(collection, details);
+
+			Self::deposit_event(Event::CollectionCreated(collection, owner));
+			Ok(())
+		}
+
+		/// Destroy `collection`, releasing its deposit.
+		///
+		/// The sender must own the collection, whose items must all have been burned.
+		#[pallet::weight(T::WeightInfo::destroy_collection())]
+		pub fn destroy_collection(
+			origin: OriginFor<T>,
+			#[pallet::compact] collection: CollectionId,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let details = Self::collection(collection).ok_or(Error::<T>::UnknownCollection)?;
+			ensure!(details.owner == who, Error::<T>::NotCollectionOwner);
+			ensure!(details.items == 0, Error::<T>::CollectionNotEmpty);
+
+			<Collections<T>>::remove(collection);
+			T::Currency::unreserve(&details.owner, details.deposit);
+
+			Self::deposit_event(Event::CollectionDestroyed(collection));
+			Ok(())
+		}
+
+		/// Mint `item` in `collection`, owned by `owner`, reserving `ItemDeposit` from the sender.
+		///
+		/// The sender must own the collection.
+		#[pallet::weight(T::WeightInfo::mint())]
+		pub fn mint(
+			origin: OriginFor<T>,
+			#[pallet::compact] collection: CollectionId,
+			#[pallet::compact] item: ItemId,
+			owner: <T::Lookup as StaticLookup>::Source,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let owner = T::Lookup::lookup(owner)?;
+
+			let deposit = T::ItemDeposit::get();
+			<Collections<T>>::try_mutate(collection, |details| -> DispatchResult {
+				let details = details.as_mut().ok_or(Error::<T>::UnknownCollection)?;
+				ensure!(details.owner == who, Error::<T>::NotCollectionOwner);
+				ensure!(!<Items<T>>::contains_key(collection, item), Error::<T>::AlreadyExists);
+				T::Currency::reserve(&who, deposit)?;
+
+				details.items = details.items.saturating_add(1);
+				Ok(())
+			})?;
+			<Items<T>>::insert(collection, item, ItemDetails { owner: owner.clone(), deposit });
+			<Owned<T>>::insert(&owner, (collection, item), ());
+
+			Self::deposit_event(Event::Minted(collection, item, owner));
+			Ok(())
+		}
+
+		/// Transfer `item` of `collection` to `dest`.
+		///
+		/// The sender must own the item.
+		#[pallet::weight(T::WeightInfo::transfer())]
+		pub fn transfer(
+			origin: OriginFor<T>,
+			#[pallet::compact] collection: CollectionId,
+			#[pallet::compact] item: ItemId,
+			dest: <T::Lookup as StaticLookup>::Source,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let dest = T::Lookup::lookup(dest)?;
+
+			let owner = <Items<T>>::try_mutate(collection, item, |details| {
+				let details = details.as_mut().ok_or(Error::<T>::UnknownItem)?;
+				ensure!(details.owner == who, Error::<T>::NotItemOwner);
+				Ok::<_, Error<T>>(sp_std::mem::replace(&mut details.owner, dest.clone()))
+			})?;
+			<Owned<T>>::remove(&owner, (collection, item));
+			<Owned<T>>::insert(&dest, (collection, item), ());
+
+			Self::deposit_event(Event::Transferred(collection, item, owner, dest));
+			Ok(())
+		}
+
+		/// Burn `item` of `collection`, releasing its deposit and the deposit of its metadata.
+		///
+		/// The sender must own the item. The deposits are released to whoever paid them, not to
+		/// the sender.
+		#[pallet::weight(T::WeightInfo::burn())]
+		pub fn burn(
+			origin: OriginFor<T>,
+			#[pallet::compact] collection: CollectionId,
+			#[pallet::compact] item: ItemId,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let ItemDetails { owner, deposit } =
+				Self::item(collection, item).ok_or(Error::<T>::UnknownItem)?;
+			ensure!(owner == who, Error::<T>::NotItemOwner);
+
+			<Collections<T>>::mutate(collection, |details| {
+				if let Some(details) = details {
+					details.items = details.items.saturating_sub(1);
+					T::Currency::unreserve(&details.owner, deposit);
+				}
+			});
+			<Items<T>>::remove(collection, item);
+			<Owned<T>>::remove(&owner, (collection, item));
+			if let Some(metadata) = <Metadata<T>>::take(collection, item) {
+				T::Currency::unreserve(&metadata.depositor, metadata.deposit);
+			}
+
+			Self::deposit_event(Event::Burned(collection, item, owner));
+			Ok(())
+		}
+
+		/// Set the metadata of `item` in `collection`, reserving a deposit from the sender.
+		///
+		/// The sender must own the collection. When replacing metadata the sender paid for, only
+		/// the difference between the deposits is reserved or released.
+		#[pallet::weight(T::WeightInfo::set_metadata(data.len() as u32))]
+		pub fn set_metadata(
+			origin: OriginFor<T>,
+			#[pallet::compact] collection: CollectionId,
+			#[pallet::compact] item: ItemId,
+			data: Vec<u8>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::ensure_collection_owner(collection, &who)?;
+			ensure!(<Items<T>>::contains_key(collection, item), Error::<T>::UnknownItem);
+			ensure!(data.len() <= T::StringLimit::get() as usize, Error::<T>::MetadataTooLong);
+
+			let deposit = T::MetadataDepositPerByte::get()
+				.saturating_mul((data.len() as u32).into())
+				.saturating_add(T::MetadataDepositBase::get());
+			match <Metadata<T>>::get(collection, item) {
+				Some(previous) if previous.depositor == who => {
+					if deposit > previous.deposit {
+						T::Currency::reserve(&who, deposit.saturating_sub(previous.deposit))?;
+					} else {
+						T::Currency::unreserve(&who, previous.deposit.saturating_sub(deposit));
+					}
+				},
+				previous => {
+					T::Currency::reserve(&who, deposit)?;
+					if let Some(previous) = previous {
+						T::Currency::unreserve(&previous.depositor, previous.deposit);
+					}
+				},
+			}
+			<Metadata<T>>::insert(collection, item, ItemMetadata { depositor: who, deposit, data });
+
+			Self::deposit_event(Event::MetadataSet(collection, item));
+			Ok(())
+		}
+
+		/// Clear the metadata of `item` in `collection`, releasing its deposit.
+		///
+		/// The sender must own the collection.
+		#[pallet::weight(T::WeightInfo::clear_metadata())]
+		pub fn clear_metadata(
+			origin: OriginFor<T>,
+			#[pallet::compact] collection: CollectionId,
+			#[pallet::compact] item: ItemId,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::ensure_collection_owner(collection, &who)?;
+
+			let metadata = <Metadata<T>>::take(collection, item).ok_or(Error::<T>::NoMetadata)?;
+			T::Currency::unreserve(&metadata.depositor, metadata.deposit);
+
+			Self::deposit_event(Event::MetadataCleared(collection, item));
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// The account holding `item` of `collection`, if it was minted.
+		pub fn owner_of(collection: CollectionId, item: ItemId) -> Option<T::AccountId> {
+			Self::item(collection, item).map(|details| details.owner)
+		}
+
+		fn ensure_collection_owner(collection: CollectionId, who: &T::AccountId) -> DispatchResult {
+			let details = Self::collection(collection).ok_or(Error::<T>::UnknownCollection)?;
+			ensure!(&details.owner == who, Error::<T>::NotCollectionOwner);
+			Ok(())
+		}
+	}
+}
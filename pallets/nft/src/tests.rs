@@ -0,0 +1,241 @@
+use crate::{mock::*, CollectionDetails, Error, Event as NftEvent};
+use frame_support::{assert_noop, assert_ok, traits::{Currency, Get}};
+
+/// The deposits reserved from `1` by `mint_to`.
+fn minted_deposits() -> u64 {
+	CollectionDeposit::get() + ItemDeposit::get()
+}
+
+/// Create collection `0`, owned by `1`, with item `0` minted to `owner`.
+fn mint_to(owner: u64) {
+	assert_ok!(Nft::create_collection(Origin::signed(1)));
+	assert_ok!(Nft::mint(Origin::signed(1), 0, 0, owner));
+}
+
+fn owned(who: u64) -> Vec<(u32, u32)> {
+	let mut items = crate::Owned::<Test>::iter_prefix(who).map(|(item, _)| item).collect::<Vec<_>>();
+	items.sort();
+	items
+}
+
+#[test]
+fn collections_get_increasing_ids() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Nft::create_collection(Origin::signed(1)));
+		System::assert_last_event(NftEvent::CollectionCreated(0, 1).into());
+		assert_ok!(Nft::create_collection(Origin::signed(2)));
+		System::assert_last_event(NftEvent::CollectionCreated(1, 2).into());
+
+		let deposit = CollectionDeposit::get();
+		assert_eq!(Nft::collection(0), Some(CollectionDetails { owner: 1, deposit, items: 0 }));
+		assert_eq!(Nft::collection(1), Some(CollectionDetails { owner: 2, deposit, items: 0 }));
+		assert_eq!(Nft::next_collection_id(), 2);
+	});
+}
+
+#[test]
+fn collections_reserve_a_deposit() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Nft::create_collection(Origin::signed(3)),
+			pallet_balances::Error::<Test>::InsufficientBalance,
+		);
+
+		assert_ok!(Nft::create_collection(Origin::signed(1)));
+		assert_eq!(Balances::reserved_balance(1), CollectionDeposit::get());
+	});
+}
+
+#[test]
+fn collection_owner_destroys_empty_collections() {
+	new_test_ext().execute_with(|| {
+		mint_to(2);
+		assert_noop!(
+			Nft::destroy_collection(Origin::signed(1), 1),
+			Error::<Test>::UnknownCollection,
+		);
+		assert_noop!(
+			Nft::destroy_collection(Origin::signed(2), 0),
+			Error::<Test>::NotCollectionOwner,
+		);
+		assert_noop!(
+			Nft::destroy_collection(Origin::signed(1), 0),
+			Error::<Test>::CollectionNotEmpty,
+		);
+
+		assert_ok!(Nft::burn(Origin::signed(2), 0, 0));
+		assert_ok!(Nft::destroy_collection(Origin::signed(1), 0));
+		System::assert_last_event(NftEvent::CollectionDestroyed(0).into());
+
+		assert_eq!(Nft::collection(0), None);
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::free_balance(1), INITIAL_BALANCE);
+	});
+}
+
+#[test]
+fn collection_owner_mints() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(Nft::mint(Origin::signed(1), 0, 0, 2), Error::<Test>::UnknownCollection);
+		assert_ok!(Nft::create_collection(Origin::signed(1)));
+		assert_noop!(Nft::mint(Origin::signed(2), 0, 0, 2), Error::<Test>::NotCollectionOwner);
+
+		assert_ok!(Nft::mint(Origin::signed(1), 0, 0, 2));
+		System::assert_last_event(NftEvent::Minted(0, 0, 2).into());
+		assert_ok!(Nft::mint(Origin::signed(1), 0, 1, 2));
+
+		assert_eq!(Nft::owner_of(0, 0), Some(2));
+		assert_eq!(Nft::collection(0).unwrap().items, 2);
+		assert_eq!(owned(2), vec![(0, 0), (0, 1)]);
+		assert_noop!(Nft::mint(Origin::signed(1), 0, 0, 1), Error::<Test>::AlreadyExists);
+
+		// The collection owner pays for the items, not their owner.
+		let reserved = CollectionDeposit::get() + 2 * ItemDeposit::get();
+		assert_eq!(Balances::reserved_balance(1), reserved);
+		assert_eq!(Balances::reserved_balance(2), 0);
+	});
+}
+
+#[test]
+fn item_deposit_must_be_affordable() {
+	new_test_ext().execute_with(|| {
+		Balances::make_free_balance_be(&3, CollectionDeposit::get());
+		assert_ok!(Nft::create_collection(Origin::signed(3)));
+
+		assert_noop!(
+			Nft::mint(Origin::signed(3), 0, 0, 3),
+			pallet_balances::Error::<Test>::InsufficientBalance,
+		);
+		assert_eq!(Nft::collection(0).unwrap().items, 0);
+	});
+}
+
+#[test]
+fn item_owner_transfers() {
+	new_test_ext().execute_with(|| {
+		mint_to(2);
+		assert_noop!(Nft::transfer(Origin::signed(1), 0, 0, 3), Error::<Test>::NotItemOwner);
+		assert_noop!(Nft::transfer(Origin::signed(2), 0, 1, 3), Error::<Test>::UnknownItem);
+
+		assert_ok!(Nft::transfer(Origin::signed(2), 0, 0, 3));
+		System::assert_last_event(NftEvent::Transferred(0, 0, 2, 3).into());
+
+		assert_eq!(Nft::owner_of(0, 0), Some(3));
+		assert!(owned(2).is_empty());
+		assert_eq!(owned(3), vec![(0, 0)]);
+	});
+}
+
+#[test]
+fn item_owner_burns() {
+	new_test_ext().execute_with(|| {
+		mint_to(2);
+		assert_noop!(Nft::burn(Origin::signed(1), 0, 0), Error::<Test>::NotItemOwner);
+
+		assert_ok!(Nft::burn(Origin::signed(2), 0, 0));
+		System::assert_last_event(NftEvent::Burned(0, 0, 2).into());
+
+		assert_eq!(Nft::owner_of(0, 0), None);
+		assert_eq!(Nft::collection(0).unwrap().items, 0);
+		assert!(owned(2).is_empty());
+		assert_noop!(Nft::burn(Origin::signed(2), 0, 0), Error::<Test>::UnknownItem);
+
+		// The item deposit is released to the collection owner.
+		assert_eq!(Balances::reserved_balance(1), CollectionDeposit::get());
+	});
+}
+
+#[test]
+fn metadata_reserves_a_deposit() {
+	new_test_ext().execute_with(|| {
+		mint_to(2);
+		assert_noop!(
+			Nft::set_metadata(Origin::signed(2), 0, 0, b"ipfs://item".to_vec()),
+			Error::<Test>::NotCollectionOwner,
+		);
+		assert_noop!(
+			Nft::set_metadata(Origin::signed(1), 0, 1, b"ipfs://item".to_vec()),
+			Error::<Test>::UnknownItem,
+		);
+		assert_noop!(
+			Nft::set_metadata(Origin::signed(1), 0, 0, vec![0; StringLimit::get() as usize + 1]),
+			Error::<Test>::MetadataTooLong,
+		);
+
+		assert_ok!(Nft::set_metadata(Origin::signed(1), 0, 0, b"ipfs://item".to_vec()));
+		System::assert_last_event(NftEvent::MetadataSet(0, 0).into());
+
+		let deposit = MetadataDepositBase::get() + 11 * MetadataDepositPerByte::get();
+		assert_eq!(Nft::metadata(0, 0).unwrap().data, b"ipfs://item".to_vec());
+		assert_eq!(Balances::reserved_balance(1), minted_deposits() + deposit);
+
+		// Replacing the metadata adjusts the deposit.
+		assert_ok!(Nft::set_metadata(Origin::signed(1), 0, 0, b"ipfs://x".to_vec()));
+		let deposit = MetadataDepositBase::get() + 8 * MetadataDepositPerByte::get();
+		assert_eq!(Balances::reserved_balance(1), minted_deposits() + deposit);
+	});
+}
+
+#[test]
+fn replacing_metadata_reserves_only_the_difference() {
+	new_test_ext().execute_with(|| {
+		let deposit = |len: u64| MetadataDepositBase::get() + len * MetadataDepositPerByte::get();
+		let free = minted_deposits() + deposit(11) + 5 * MetadataDepositPerByte::get();
+		Balances::make_free_balance_be(&3, free);
+		assert_ok!(Nft::create_collection(Origin::signed(3)));
+		assert_ok!(Nft::mint(Origin::signed(3), 0, 0, 3));
+		assert_ok!(Nft::set_metadata(Origin::signed(3), 0, 0, b"ipfs://item".to_vec()));
+
+		// The free balance left covers 5 more bytes, not the whole deposit of the new metadata.
+		assert_ok!(Nft::set_metadata(Origin::signed(3), 0, 0, b"ipfs://item/0001".to_vec()));
+		assert_eq!(Balances::reserved_balance(3), minted_deposits() + deposit(16));
+		assert_eq!(Balances::free_balance(3), 0);
+	});
+}
+
+#[test]
+fn metadata_deposit_must_be_affordable() {
+	new_test_ext().execute_with(|| {
+		Balances::make_free_balance_be(&3, minted_deposits());
+		assert_ok!(Nft::create_collection(Origin::signed(3)));
+		assert_ok!(Nft::mint(Origin::signed(3), 0, 0, 3));
+
+		assert_noop!(
+			Nft::set_metadata(Origin::signed(3), 0, 0, b"ipfs://item".to_vec()),
+			pallet_balances::Error::<Test>::InsufficientBalance,
+		);
+	});
+}
+
+#[test]
+fn clearing_metadata_releases_the_deposit() {
+	new_test_ext().execute_with(|| {
+		mint_to(2);
+		assert_noop!(Nft::clear_metadata(Origin::signed(1), 0, 0), Error::<Test>::NoMetadata);
+		assert_ok!(Nft::set_metadata(Origin::signed(1), 0, 0, b"ipfs://item".to_vec()));
+		assert_noop!(Nft::clear_metadata(Origin::signed(2), 0, 0), Error::<Test>::NotCollectionOwner);
+
+		assert_ok!(Nft::clear_metadata(Origin::signed(1), 0, 0));
+		System::assert_last_event(NftEvent::MetadataCleared(0, 0).into());
+
+		assert_eq!(Nft::metadata(0, 0), None);
+		assert_eq!(Balances::reserved_balance(1), minted_deposits());
+		assert_eq!(Balances::free_balance(1), INITIAL_BALANCE - minted_deposits());
+	});
+}
+
+#[test]
+fn burning_releases_the_metadata_deposit() {
+	new_test_ext().execute_with(|| {
+		mint_to(2);
+		assert_ok!(Nft::set_metadata(Origin::signed(1), 0, 0, b"ipfs://item".to_vec()));
+
+		// The deposit is released to the collection owner who paid it, not the item owner.
+		assert_ok!(Nft::burn(Origin::signed(2), 0, 0));
+
+		assert_eq!(Nft::metadata(0, 0), None);
+		assert_eq!(Balances::reserved_balance(1), CollectionDeposit::get());
+		assert_eq!(Balances::free_balance(1), INITIAL_BALANCE - CollectionDeposit::get());
+		assert_eq!(Balances::free_balance(2), INITIAL_BALANCE);
+	});
+}
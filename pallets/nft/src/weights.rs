@@ -0,0 +1,63 @@
+//! Weights for pallet_nft.
+//!
+//! NOT YET GENERATED: there are no benchmarked weights yet. The estimates of the `()`
+//! implementation, which the runtime uses in the meantime, are scaled on comparable
+//! `pallet_assets` calls and only count the storage accesses from the code.
+//! `./scripts/benchmark.sh nft` run on reference hardware replaces this file with measured
+//! `SubstrateWeight`s, which the runtime should then use.
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+
+/// Weight functions needed for pallet_nft.
+pub trait WeightInfo {
+	fn create_collection() -> Weight;
+	fn destroy_collection() -> Weight;
+	fn mint() -> Weight;
+	fn transfer() -> Weight;
+	fn burn() -> Weight;
+	fn set_metadata(s: u32, ) -> Weight;
+	fn clear_metadata() -> Weight;
+}
+
+/// Estimated weights for pallet_nft, until they are benchmarked.
+impl WeightInfo for () {
+	fn create_collection() -> Weight {
+		(30_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+	fn destroy_collection() -> Weight {
+		(30_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	fn mint() -> Weight {
+		(40_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
+	}
+	fn transfer() -> Weight {
+		(40_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+	fn burn() -> Weight {
+		(45_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(5 as Weight))
+	}
+	fn set_metadata(s: u32, ) -> Weight {
+		(50_000_000 as Weight)
+			.saturating_add((10_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+	fn clear_metadata() -> Weight {
+		(45_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+}
@@ -0,0 +1,288 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+/// Aggregates values submitted by a set of authorized feeders.
+///
+/// Feeders, managed by root, submit values for any number of keys. At the end of each block,
+/// the value of every key fed during the block is aggregated as the median of the fresh values
+/// of the current feeders. Values older than `MaxAge` blocks are stale: they are left out of
+/// the median, and aggregated values are no longer provided once they get that old.
+///
+/// Other pallets read the aggregated values through `DataProvider`.
+
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+/// Provides values by key, e.g. prices by currency.
+pub trait DataProvider<Key, Value> {
+	/// The current value of `key`, if any.
+	fn get(key: &Key) -> Option<Value>;
+}
+
+/// Provides no value.
+impl<Key, Value> DataProvider<Key, Value> for () {
+	fn get(_key: &Key) -> Option<Value> {
+		None
+	}
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use frame_support::{dispatch::DispatchResult, pallet_prelude::*};
+	use frame_system::pallet_prelude::*;
+	use sp_runtime::traits::Saturating;
+	use sp_std::prelude::*;
+	use crate::DataProvider;
+
+	/// A value and its age.
+	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug)]
+	pub struct TimestampedValue<Value, BlockNumber> {
+		/// The value itself.
+		pub value: Value,
+		/// The block in which the value was submitted or aggregated.
+		pub timestamp: BlockNumber,
+	}
+
+	pub(crate) type TimestampedValueOf<T> =
+		TimestampedValue<<T as Config>::OracleValue, <T as frame_system::Config>::BlockNumber>;
+
+	/// Configure the pallet by specifying the parameters and types on which it depends.
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// Because this pallet emits events, it depends on the runtime's definition of an event.
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+		/// The key values are submitted for.
+		type OracleKey: Parameter + Member;
+		/// The values submitted, ordered to compute their median.
+		type OracleValue: Parameter + Member + Ord;
+		/// The number of blocks after which a value is stale.
+		#[pallet::constant]
+		type MaxAge: Get<Self::BlockNumber>;
+		/// The maximum number of feeders.
+		#[pallet::constant]
+		type MaxFeeders: Get<u32>;
+	}
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	/// The accounts allowed to submit values.
+	#[pallet::storage]
+	#[pallet::getter(fn feeders)]
+	pub type Feeders<T: Config> = StorageValue<_, Vec<T::AccountId>, ValueQuery>;
+
+	/// The last value submitted by each feeder, for each key.
+	#[pallet::storage]
+	#[pallet::getter(fn raw_value)]
+	pub type RawValues<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		T::OracleKey,
+		Blake2_128Concat,
+		T::AccountId,
+		TimestampedValueOf<T>,
+	>;
+
+	/// The keys fed in the current block, aggregated when the block is finalized.
+	#[pallet::storage]
+	pub type UpdatedKeys<T: Config> = StorageValue<_, Vec<T::OracleKey>, ValueQuery>;
+
+	/// The last aggregated value of each key, regardless of its age.
+	#[pallet::storage]
+	#[pallet::getter(fn aggregated_value)]
+	pub type AggregatedValues<T: Config> =
+		StorageMap<_, Twox64Concat, T::OracleKey, TimestampedValueOf<T>>;
+
+	#[pallet::genesis_config]
+	pub struct GenesisConfig<T: Config> {
+		/// The accounts allowed to submit values.
+		pub feeders: Vec<T::AccountId>,
+	}
+
+	#[cfg(feature = "std")]
+	impl<T: Config> Default for GenesisConfig<T> {
+		fn default() -> Self {
+			Self { feeders: Vec::new() }
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
+		fn build(&self) {
+			assert!(
+				self.feeders.len() <= T::MaxFeeders::get() as usize,
+				"there can be at most `MaxFeeders` feeders",
+			);
+			<Feeders<T>>::put(&self.feeders);
+		}
+	}
+
+	#[pallet::event]
+	#[pallet::metadata(
+		T::AccountId = "AccountId",
+		T::OracleKey = "OracleKey",
+		T::OracleValue = "OracleValue",
+		Vec<(T::OracleKey, T::OracleValue)> = "Vec<(OracleKey, OracleValue)>"
+	)]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// A feeder was added. [feeder]
+		FeederAdded(T::AccountId),
+		/// A feeder was removed. [feeder]
+		FeederRemoved(T::AccountId),
+		/// A feeder submitted values. [feeder, values]
+		NewFeedData(T::AccountId, Vec<(T::OracleKey, T::OracleValue)>),
+		/// The value of a key was aggregated. [key, value]
+		NewAggregatedValue(T::OracleKey, T::OracleValue),
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The sender is not allowed to submit values.
+		NotFeeder,
+		/// The account is a feeder already.
+		AlreadyFeeder,
+		/// There are `MaxFeeders` feeders already.
+		TooManyFeeders,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		/// Reserve the weight of aggregating the keys fed during the block, which is charged
+		/// per key to the feeders.
+		fn on_initialize(_now: T::BlockNumber) -> Weight {
+			T::DbWeight::get().reads_writes(1, 1)
+		}
+
+		/// Aggregate the keys fed during the block.
+		fn on_finalize(now: T::BlockNumber) {
+			for key in <UpdatedKeys<T>>::take() {
+				if let Some(value) = Self::median(&key, now) {
+					<AggregatedValues<T>>::insert(
+						&key,
+						TimestampedValue { value: value.clone(), timestamp: now },
+					);
+					Self::deposit_event(Event::NewAggregatedValue(key, value));
+				}
+			}
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Submit the current value of some keys.
+		///
+		/// The sender must be a feeder.
+		#[pallet::weight(
+			10_000 + T::DbWeight::get().reads_writes(
+				2 + T::MaxFeeders::get() as Weight * values.len() as Weight,
+				1 + 2 * values.len() as Weight,
+			)
+		)]
+		pub fn feed_values(
+			origin: OriginFor<T>,
+			values: Vec<(T::OracleKey, T::OracleValue)>,
+		) -> DispatchResult {
+			let feeder = ensure_signed(origin)?;
+			ensure!(Self::feeders().contains(&feeder), Error::<T>::NotFeeder);
+
+			let now = <frame_system::Pallet<T>>::block_number();
+			<UpdatedKeys<T>>::mutate(|updated| {
+				for (key, value) in &values {
+					<RawValues<T>>::insert(
+						key,
+						&feeder,
+						TimestampedValue { value: value.clone(), timestamp: now },
+					);
+					if !updated.contains(key) {
+						updated.push(key.clone());
+					}
+				}
+			});
+
+			Self::deposit_event(Event::NewFeedData(feeder, values));
+			Ok(())
+		}
+
+		/// Allow `feeder` to submit values.
+		///
+		/// The dispatch origin for this call must be _Root_.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1, 1))]
+		pub fn add_feeder(origin: OriginFor<T>, feeder: T::AccountId) -> DispatchResult {
+			ensure_root(origin)?;
+
+			<Feeders<T>>::try_mutate(|feeders| -> DispatchResult {
+				ensure!(!feeders.contains(&feeder), Error::<T>::AlreadyFeeder);
+				ensure!(
+					(feeders.len() as u32) < T::MaxFeeders::get(),
+					Error::<T>::TooManyFeeders,
+				);
+				feeders.push(feeder.clone());
+				Ok(())
+			})?;
+
+			Self::deposit_event(Event::FeederAdded(feeder));
+			Ok(())
+		}
+
+		/// Stop allowing `feeder` to submit values.
+		///
+		/// The values it submitted are left out of the medians aggregated from now on.
+		///
+		/// The dispatch origin for this call must be _Root_.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1, 1))]
+		pub fn remove_feeder(origin: OriginFor<T>, feeder: T::AccountId) -> DispatchResult {
+			ensure_root(origin)?;
+
+			<Feeders<T>>::try_mutate(|feeders| -> DispatchResult {
+				let index = feeders.iter().position(|f| f == &feeder).ok_or(Error::<T>::NotFeeder)?;
+				feeders.swap_remove(index);
+				Ok(())
+			})?;
+
+			Self::deposit_event(Event::FeederRemoved(feeder));
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// Whether a value from block `timestamp` is still fresh in block `now`.
+		fn is_fresh(timestamp: T::BlockNumber, now: T::BlockNumber) -> bool {
+			now.saturating_sub(timestamp) <= T::MaxAge::get()
+		}
+
+		/// The median of the fresh values of the current feeders for `key`.
+		///
+		/// With an even number of values, the lower of the two middle values is taken, so
+		/// that the median is always one of the values submitted.
+		fn median(key: &T::OracleKey, now: T::BlockNumber) -> Option<T::OracleValue> {
+			let mut values = Self::feeders()
+				.iter()
+				.filter_map(|feeder| Self::raw_value(key, feeder))
+				.filter(|raw| Self::is_fresh(raw.timestamp, now))
+				.map(|raw| raw.value)
+				.collect::<Vec<_>>();
+			if values.is_empty() {
+				return None
+			}
+
+			values.sort();
+			Some(values.swap_remove((values.len() - 1) / 2))
+		}
+	}
+
+	impl<T: Config> DataProvider<T::OracleKey, T::OracleValue> for Pallet<T> {
+		/// The aggregated value of `key`, unless it is stale.
+		fn get(key: &T::OracleKey) -> Option<T::OracleValue> {
+			let now = <frame_system::Pallet<T>>::block_number();
+			Self::aggregated_value(key)
+				.filter(|aggregated| Self::is_fresh(aggregated.timestamp, now))
+				.map(|aggregated| aggregated.value)
+		}
+	}
+}
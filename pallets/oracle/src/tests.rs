@@ -0,0 +1,116 @@
+use crate::{mock::*, DataProvider, Error, Event as OracleEvent, TimestampedValue};
+use frame_support::{assert_noop, assert_ok, traits::OnFinalize};
+use sp_runtime::traits::BadOrigin;
+
+/// Finalize the current block, then start the next one.
+fn next_block() {
+	let now = System::block_number();
+	Oracle::on_finalize(now);
+	System::set_block_number(now + 1);
+}
+
+#[test]
+fn only_feeders_feed() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(Oracle::feed_values(Origin::signed(4), vec![(KEY, 10)]), Error::<Test>::NotFeeder);
+
+		assert_ok!(Oracle::feed_values(Origin::signed(1), vec![(KEY, 10)]));
+		System::assert_last_event(OracleEvent::NewFeedData(1, vec![(KEY, 10)]).into());
+		assert_eq!(Oracle::raw_value(KEY, 1), Some(TimestampedValue { value: 10, timestamp: 1 }));
+	});
+}
+
+#[test]
+fn median_is_aggregated_on_finalize() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Oracle::feed_values(Origin::signed(1), vec![(KEY, 10)]));
+		assert_ok!(Oracle::feed_values(Origin::signed(2), vec![(KEY, 30)]));
+		assert_ok!(Oracle::feed_values(Origin::signed(3), vec![(KEY, 20)]));
+		assert_eq!(<Oracle as DataProvider<_, _>>::get(&KEY), None);
+
+		next_block();
+
+		assert_eq!(<Oracle as DataProvider<_, _>>::get(&KEY), Some(20));
+		assert_eq!(Oracle::aggregated_value(KEY), Some(TimestampedValue { value: 20, timestamp: 1 }));
+		System::assert_last_event(OracleEvent::NewAggregatedValue(KEY, 20).into());
+	});
+}
+
+#[test]
+fn median_of_an_even_number_of_values_is_the_lower_one() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Oracle::feed_values(Origin::signed(1), vec![(KEY, 30), (KEY + 1, 7)]));
+		assert_ok!(Oracle::feed_values(Origin::signed(2), vec![(KEY, 10)]));
+
+		next_block();
+
+		assert_eq!(<Oracle as DataProvider<_, _>>::get(&KEY), Some(10));
+		assert_eq!(<Oracle as DataProvider<_, _>>::get(&(KEY + 1)), Some(7));
+	});
+}
+
+#[test]
+fn stale_values_are_left_out() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Oracle::feed_values(Origin::signed(1), vec![(KEY, 1_000)]));
+		for _ in 0..MaxAge::get() + 1 {
+			next_block();
+		}
+
+		assert_ok!(Oracle::feed_values(Origin::signed(2), vec![(KEY, 10)]));
+		assert_ok!(Oracle::feed_values(Origin::signed(3), vec![(KEY, 20)]));
+		next_block();
+
+		// Only 10 and 20 are fresh, the value of feeder 1 would have made 20 the median.
+		assert_eq!(<Oracle as DataProvider<_, _>>::get(&KEY), Some(10));
+	});
+}
+
+#[test]
+fn stale_aggregated_values_are_not_provided() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Oracle::feed_values(Origin::signed(1), vec![(KEY, 10)]));
+		next_block();
+
+		System::set_block_number(1 + MaxAge::get());
+		assert_eq!(<Oracle as DataProvider<_, _>>::get(&KEY), Some(10));
+
+		System::set_block_number(2 + MaxAge::get());
+		assert_eq!(<Oracle as DataProvider<_, _>>::get(&KEY), None);
+		// The last aggregated value is kept for reference.
+		assert!(Oracle::aggregated_value(KEY).is_some());
+	});
+}
+
+#[test]
+fn root_manages_feeders() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(Oracle::add_feeder(Origin::signed(1), 4), BadOrigin);
+		assert_noop!(Oracle::add_feeder(Origin::root(), 1), Error::<Test>::AlreadyFeeder);
+
+		assert_ok!(Oracle::add_feeder(Origin::root(), 4));
+		System::assert_last_event(OracleEvent::FeederAdded(4).into());
+		assert_eq!(Oracle::feeders(), vec![1, 2, 3, 4]);
+		assert_noop!(Oracle::add_feeder(Origin::root(), 5), Error::<Test>::TooManyFeeders);
+
+		assert_noop!(Oracle::remove_feeder(Origin::signed(1), 4), BadOrigin);
+		assert_noop!(Oracle::remove_feeder(Origin::root(), 5), Error::<Test>::NotFeeder);
+		assert_ok!(Oracle::remove_feeder(Origin::root(), 1));
+		System::assert_last_event(OracleEvent::FeederRemoved(1).into());
+		assert!(!Oracle::feeders().contains(&1));
+	});
+}
+
+#[test]
+fn values_of_removed_feeders_are_left_out() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Oracle::feed_values(Origin::signed(1), vec![(KEY, 1_000)]));
+		assert_ok!(Oracle::feed_values(Origin::signed(2), vec![(KEY, 10)]));
+		assert_ok!(Oracle::remove_feeder(Origin::root(), 1));
+
+		next_block();
+
+		assert_eq!(<Oracle as DataProvider<_, _>>::get(&KEY), Some(10));
+		assert_noop!(Oracle::feed_values(Origin::signed(1), vec![(KEY, 10)]), Error::<Test>::NotFeeder);
+	});
+}
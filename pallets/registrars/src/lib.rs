@@ -0,0 +1,71 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+/// Seeds `pallet_identity` with registrars at genesis, so that a fresh chain can hand out
+/// judgements without a root call. `pallet_identity` has no genesis configuration of its own.
+
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use frame_support::{pallet_prelude::*, traits::Currency};
+	use frame_system::{pallet_prelude::*, RawOrigin};
+	use sp_std::prelude::*;
+
+	type BalanceOf<T> = <<T as pallet_identity::Config>::Currency as Currency<
+		<T as frame_system::Config>::AccountId,
+	>>::Balance;
+
+	/// The registrars are added through `pallet_identity::add_registrar`, so its
+	/// `RegistrarOrigin` must accept the root origin.
+	#[pallet::config]
+	pub trait Config: pallet_identity::Config {}
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {}
+
+	#[pallet::genesis_config]
+	pub struct GenesisConfig<T: Config> {
+		/// The registrars to add, in index order, with the fee each charges for a judgement.
+		pub registrars: Vec<(T::AccountId, BalanceOf<T>)>,
+	}
+
+	#[cfg(feature = "std")]
+	impl<T: Config> Default for GenesisConfig<T> {
+		fn default() -> Self {
+			Self { registrars: Vec::new() }
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
+		fn build(&self) {
+			assert!(
+				self.registrars.len() <= T::MaxRegistrars::get() as usize,
+				"more registrars at genesis than `MaxRegistrars`",
+			);
+			for (index, (account, fee)) in self.registrars.iter().enumerate() {
+				pallet_identity::Pallet::<T>::add_registrar(
+					RawOrigin::Root.into(),
+					account.clone(),
+				).expect("`RegistrarOrigin` must accept root to add registrars at genesis");
+				pallet_identity::Pallet::<T>::set_fee(
+					RawOrigin::Signed(account.clone()).into(),
+					index as u32,
+					*fee,
+				).expect("the registrar was added just above; qed");
+			}
+		}
+	}
+}
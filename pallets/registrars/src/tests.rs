@@ -0,0 +1,24 @@
+use crate::mock::*;
+
+#[test]
+fn genesis_adds_the_registrars_with_their_fees() {
+	new_test_ext(vec![(10, 5), (11, 0)]).execute_with(|| {
+		let registrars = Identity::registrars().into_iter()
+			.map(|registrar| registrar.map(|registrar| (registrar.account, registrar.fee)))
+			.collect::<Vec<_>>();
+		assert_eq!(registrars, vec![Some((10, 5)), Some((11, 0))]);
+	});
+}
+
+#[test]
+fn genesis_without_registrars_is_empty() {
+	new_test_ext(vec![]).execute_with(|| {
+		assert!(Identity::registrars().is_empty());
+	});
+}
+
+#[test]
+#[should_panic(expected = "more registrars at genesis than `MaxRegistrars`")]
+fn genesis_rejects_more_registrars_than_the_identity_pallet_allows() {
+	new_test_ext(vec![(10, 0), (11, 0), (12, 0)]);
+}
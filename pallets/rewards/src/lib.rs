@@ -0,0 +1,151 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+/// Mints a reward to the author of every block.
+///
+/// The pallet is meant to be the `EventHandler` of `pallet_authorship`, which notes the author
+/// of each block when it is initialized.
+
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use frame_support::{dispatch::DispatchResult, pallet_prelude::*, traits::{Currency, Imbalance}};
+	use frame_system::pallet_prelude::*;
+	use sp_runtime::traits::{AtLeast32BitUnsigned, One, Zero};
+
+	pub(crate) type BalanceOf<T> =
+		<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+	/// Derives the reward of a block from the base block reward.
+	pub trait RewardCurve<BlockNumber, Balance> {
+		/// The reward for authoring block `now`, given the base reward `base`.
+		fn reward(base: Balance, now: BlockNumber) -> Balance;
+	}
+
+	/// A flat curve, every block is rewarded with the base reward.
+	impl<BlockNumber, Balance> RewardCurve<BlockNumber, Balance> for () {
+		fn reward(base: Balance, _now: BlockNumber) -> Balance {
+			base
+		}
+	}
+
+	/// A curve halving the base reward every `Period` blocks.
+	pub struct Halving<Period>(PhantomData<Period>);
+
+	impl<BlockNumber, Balance, Period> RewardCurve<BlockNumber, Balance> for Halving<Period> where
+		BlockNumber: AtLeast32BitUnsigned,
+		Balance: AtLeast32BitUnsigned,
+		Period: Get<BlockNumber>,
+	{
+		fn reward(base: Balance, now: BlockNumber) -> Balance {
+			let period = Period::get();
+			if period.is_zero() {
+				return base
+			}
+
+			let mut halvings = now / period;
+			let mut reward = base;
+			while !halvings.is_zero() && !reward.is_zero() {
+				reward = reward / 2u32.into();
+				halvings = halvings - One::one();
+			}
+			reward
+		}
+	}
+
+	/// Configure the pallet by specifying the parameters and types on which it depends.
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// Because this pallet emits events, it depends on the runtime's definition of an event.
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+		/// The currency in which rewards are minted.
+		type Currency: Currency<Self::AccountId>;
+		/// How the reward evolves over the lifetime of the chain.
+		type RewardCurve: RewardCurve<Self::BlockNumber, BalanceOf<Self>>;
+	}
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	/// The base reward minted for each block, before the reward curve is applied.
+	#[pallet::storage]
+	#[pallet::getter(fn block_reward)]
+	pub type BlockReward<T> = StorageValue<_, BalanceOf<T>, ValueQuery>;
+
+	#[pallet::genesis_config]
+	pub struct GenesisConfig<T: Config> {
+		/// The base reward minted for each block.
+		pub block_reward: BalanceOf<T>,
+	}
+
+	#[cfg(feature = "std")]
+	impl<T: Config> Default for GenesisConfig<T> {
+		fn default() -> Self {
+			Self { block_reward: Zero::zero() }
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
+		fn build(&self) {
+			<BlockReward<T>>::put(self.block_reward);
+		}
+	}
+
+	#[pallet::event]
+	#[pallet::metadata(T::AccountId = "AccountId", BalanceOf<T> = "Balance")]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// The author of a block was rewarded. [author, amount]
+		RewardPaid(T::AccountId, BalanceOf<T>),
+		/// The base block reward was changed. [block_reward]
+		BlockRewardSet(BalanceOf<T>),
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Set the base reward minted for each block.
+		///
+		/// The dispatch origin for this call must be _Root_.
+		#[pallet::weight(10_000 + T::DbWeight::get().writes(1))]
+		pub fn set_block_reward(
+			origin: OriginFor<T>,
+			#[pallet::compact] block_reward: BalanceOf<T>,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+
+			<BlockReward<T>>::put(block_reward);
+
+			Self::deposit_event(Event::BlockRewardSet(block_reward));
+			Ok(())
+		}
+	}
+
+	impl<T: Config> pallet_authorship::EventHandler<T::AccountId, T::BlockNumber> for Pallet<T> {
+		fn note_author(author: T::AccountId) {
+			let now = <frame_system::Pallet<T>>::block_number();
+			let reward = T::RewardCurve::reward(Self::block_reward(), now);
+			if reward.is_zero() {
+				return
+			}
+
+			// Rewards below the existential deposit of a new account are not minted.
+			let minted = T::Currency::deposit_creating(&author, reward);
+			if !minted.peek().is_zero() {
+				Self::deposit_event(Event::RewardPaid(author, minted.peek()));
+			}
+		}
+
+		fn note_uncle(_author: T::AccountId, _age: T::BlockNumber) {}
+	}
+}
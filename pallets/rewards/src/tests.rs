@@ -0,0 +1,58 @@
+use crate::{mock::*, Event as RewardsEvent, Halving, RewardCurve};
+use frame_support::{assert_ok, assert_noop, parameter_types, traits::Get};
+use pallet_authorship::EventHandler;
+use sp_runtime::traits::BadOrigin;
+
+#[test]
+fn author_is_rewarded() {
+	new_test_ext().execute_with(|| {
+		let issuance = Balances::total_issuance();
+
+		Rewards::note_author(1);
+
+		assert_eq!(Balances::free_balance(1), BLOCK_REWARD);
+		assert_eq!(Balances::total_issuance(), issuance + BLOCK_REWARD);
+		System::assert_last_event(RewardsEvent::RewardPaid(1, BLOCK_REWARD).into());
+	});
+}
+
+#[test]
+fn reward_below_existential_deposit_is_not_minted() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Rewards::set_block_reward(Origin::root(), ExistentialDeposit::get() - 1));
+
+		Rewards::note_author(1);
+
+		assert_eq!(Balances::free_balance(1), 0);
+		assert_eq!(Balances::total_issuance(), 0);
+	});
+}
+
+#[test]
+fn block_reward_is_set_by_root() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(Rewards::set_block_reward(Origin::signed(1), 50), BadOrigin);
+
+		assert_ok!(Rewards::set_block_reward(Origin::root(), 50));
+		assert_eq!(Rewards::block_reward(), 50);
+		System::assert_last_event(RewardsEvent::BlockRewardSet(50).into());
+
+		Rewards::note_author(1);
+		assert_eq!(Balances::free_balance(1), 50);
+	});
+}
+
+parameter_types! {
+	pub const HalvingPeriod: u64 = 10;
+}
+
+#[test]
+fn halving_curve_halves_every_period() {
+	type Curve = Halving<HalvingPeriod>;
+
+	assert_eq!(<Curve as RewardCurve<u64, u64>>::reward(100, 0), 100);
+	assert_eq!(<Curve as RewardCurve<u64, u64>>::reward(100, 9), 100);
+	assert_eq!(<Curve as RewardCurve<u64, u64>>::reward(100, 10), 50);
+	assert_eq!(<Curve as RewardCurve<u64, u64>>::reward(100, 25), 25);
+	assert_eq!(<Curve as RewardCurve<u64, u64>>::reward(100, u64::max_value()), 0);
+}
@@ -6,6 +6,17 @@
 
 pub use pallet::*;
 
+use codec::{Decode, Encode};
+use frame_support::{
+	sp_std::{fmt, marker::PhantomData},
+	traits::{Get, IsSubType},
+	weights::Weight,
+};
+use sp_runtime::{
+	traits::{DispatchInfoOf, SignedExtension},
+	transaction_validity::{TransactionValidity, TransactionValidityError, ValidTransaction},
+};
+
 #[cfg(test)]
 mod mock;
 
@@ -15,16 +26,37 @@ mod tests;
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
 
+/// The target of the logs of this pallet, forwarded from Wasm to the node.
+pub const LOG_TARGET: &str = "runtime::template";
+
 #[frame_support::pallet]
 pub mod pallet {
-	use frame_support::{dispatch::DispatchResult, pallet_prelude::*};
+	use super::LOG_TARGET;
+	use frame_support::{
+		dispatch::DispatchResult,
+		pallet_prelude::*,
+		traits::{Currency, IsSubType},
+	};
 	use frame_system::pallet_prelude::*;
+	use sp_runtime::traits::Saturating;
+
+	type BalanceOf<T> =
+		<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
 
 	/// Configure the pallet by specifying the parameters and types on which it depends.
 	#[pallet::config]
 	pub trait Config: frame_system::Config {
 		/// Because this pallet emits events, it depends on the runtime's definition of an event.
 		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+		/// The number of blocks an account must wait between two free calls.
+		#[pallet::constant]
+		type FreeCallCooldown: Get<Self::BlockNumber>;
+		/// The currency accounts must hold `FreeCallMinBalance` of to make free calls.
+		type Currency: Currency<Self::AccountId>;
+		/// The least free balance of an account making a free call, so that free calls can't be
+		/// made from countless fresh accounts, each adding to the state.
+		#[pallet::constant]
+		type FreeCallMinBalance: Get<BalanceOf<Self>>;
 	}
 
 	#[pallet::pallet]
@@ -39,6 +71,12 @@ pub mod pallet {
 	// https://substrate.dev/docs/en/knowledgebase/runtime/storage#declaring-storage-items
 	pub type Something<T> = StorageValue<_, u32>;
 
+	/// The block in which each account last made a free call.
+	#[pallet::storage]
+	#[pallet::getter(fn last_free_call)]
+	pub type LastFreeCall<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, T::BlockNumber>;
+
 	// Pallets use events to inform users when important changes are made.
 	// https://substrate.dev/docs/en/knowledgebase/runtime/events
 	#[pallet::event]
@@ -69,6 +107,9 @@ pub mod pallet {
 	impl<T:Config> Pallet<T> {
 		/// An example dispatchable that takes a singles value as a parameter, writes the value to
 		/// storage and emits an event. This function must be dispatched by a signed extrinsic.
+		///
+		/// An account holding `FreeCallMinBalance` can make this call for free once every
+		/// `FreeCallCooldown` blocks, see `CheckFreeCall`.
 		#[pallet::weight(10_000 + T::DbWeight::get().writes(1))]
 		pub fn do_something(origin: OriginFor<T>, something: u32) -> DispatchResult {
 			// Check that the extrinsic was signed and get the signer.
@@ -78,6 +119,7 @@ pub mod pallet {
 
 			// Update storage.
 			<Something<T>>::put(something);
+			log::debug!(target: LOG_TARGET, "Something set to {} by {:?}", something, who);
 
 			// Emit an event.
 			Self::deposit_event(Event::SomethingStored(something, who));
@@ -93,15 +135,135 @@ pub mod pallet {
 			// Read a value from storage.
 			match <Something<T>>::get() {
 				// Return an error if the value has not been set.
-				None => Err(Error::<T>::NoneValue)?,
+				None => {
+					log::debug!(target: LOG_TARGET, "Something is not set, nothing to increment");
+					Err(Error::<T>::NoneValue)?
+				},
 				Some(old) => {
 					// Increment the value read from storage; will error in the event of overflow.
 					let new = old.checked_add(1).ok_or(Error::<T>::StorageOverflow)?;
 					// Update the value in storage with the incremented result.
 					<Something<T>>::put(new);
+					log::debug!(target: LOG_TARGET, "Something incremented to {}", new);
 					Ok(())
 				},
 			}
 		}
 	}
+
+	impl<T: Config> Pallet<T> {
+		/// Whether `who` may make a free call now: it holds `FreeCallMinBalance`, and made no
+		/// free call in the last `FreeCallCooldown` blocks.
+		pub fn free_call_available(who: &T::AccountId) -> bool {
+			if T::Currency::free_balance(who) < T::FreeCallMinBalance::get() {
+				return false
+			}
+			match Self::last_free_call(who) {
+				None => true,
+				Some(last) => {
+					let now = <frame_system::Pallet<T>>::block_number();
+					now >= last.saturating_add(T::FreeCallCooldown::get())
+				},
+			}
+		}
+
+		/// Whether `call`, made by `who`, is free of transaction fees.
+		pub fn is_free_call(who: &T::AccountId, call: &<T as frame_system::Config>::Call) -> bool where
+			<T as frame_system::Config>::Call: IsSubType<Call<T>>,
+		{
+			match call.is_sub_type() {
+				Some(Call::do_something(..)) => Self::free_call_available(who),
+				_ => false,
+			}
+		}
+	}
+}
+
+/// Validates and records free calls to `do_something`.
+///
+/// The extension doesn't waive any fee by itself: the runtime's fee payment extension is
+/// expected to skip free calls according to `Pallet::is_free_call`, and so to come before this
+/// extension in `SignedExtra`. A free call provides a tag unique to its sender, so that the pool
+/// accepts a single free call per account.
+///
+/// Checking whether `do_something` is free reads `LastFreeCall` and the balance of its sender,
+/// and recording a free call writes `LastFreeCall`. Neither is part of the weight of the call, so
+/// the extension adds them to the weight of the block.
+#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+pub struct CheckFreeCall<T: Config + Send + Sync>(PhantomData<T>) where
+	<T as frame_system::Config>::Call: IsSubType<Call<T>>;
+
+impl<T: Config + Send + Sync> CheckFreeCall<T> where
+	<T as frame_system::Config>::Call: IsSubType<Call<T>>,
+{
+	/// Create new `SignedExtension` to check free calls.
+	pub fn new() -> Self {
+		Self(PhantomData)
+	}
+}
+
+impl<T: Config + Send + Sync> fmt::Debug for CheckFreeCall<T> where
+	<T as frame_system::Config>::Call: IsSubType<Call<T>>,
+{
+	#[cfg(feature = "std")]
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "CheckFreeCall")
+	}
+
+	#[cfg(not(feature = "std"))]
+	fn fmt(&self, _: &mut fmt::Formatter) -> fmt::Result {
+		Ok(())
+	}
+}
+
+impl<T: Config + Send + Sync> SignedExtension for CheckFreeCall<T> where
+	<T as frame_system::Config>::Call: IsSubType<Call<T>>,
+{
+	const IDENTIFIER: &'static str = "CheckFreeCall";
+	type AccountId = T::AccountId;
+	type Call = <T as frame_system::Config>::Call;
+	type AdditionalSigned = ();
+	type Pre = ();
+
+	fn additional_signed(&self) -> Result<(), TransactionValidityError> {
+		Ok(())
+	}
+
+	fn validate(
+		&self,
+		who: &Self::AccountId,
+		call: &Self::Call,
+		_info: &DispatchInfoOf<Self::Call>,
+		_len: usize,
+	) -> TransactionValidity {
+		if Pallet::<T>::is_free_call(who, call) {
+			ValidTransaction::with_tag_prefix("TemplateFreeCall")
+				.and_provides(who)
+				.build()
+		} else {
+			Ok(ValidTransaction::default())
+		}
+	}
+
+	fn pre_dispatch(
+		self,
+		who: &Self::AccountId,
+		call: &Self::Call,
+		info: &DispatchInfoOf<Self::Call>,
+		_len: usize,
+	) -> Result<(), TransactionValidityError> {
+		if !matches!(call.is_sub_type(), Some(Call::do_something(..))) {
+			return Ok(())
+		}
+
+		let is_free = Pallet::<T>::is_free_call(who, call);
+		if is_free {
+			let now = <frame_system::Pallet<T>>::block_number();
+			log::debug!(target: LOG_TARGET, "Free call by {:?} in block #{:?}", who, now);
+			<LastFreeCall<T>>::insert(who, now);
+		}
+		let weight = T::DbWeight::get().reads_writes(2, is_free as Weight);
+		<frame_system::Pallet<T>>::register_extra_weight_unchecked(weight, info.class);
+		Ok(())
+	}
 }
@@ -1,6 +1,6 @@
 use crate as pallet_template;
 use sp_core::H256;
-use frame_support::parameter_types;
+use frame_support::{parameter_types, weights::constants::RocksDbWeight};
 use sp_runtime::{
 	traits::{BlakeTwo256, IdentityLookup}, testing::Header,
 };
@@ -17,6 +17,7 @@ frame_support::construct_runtime!(
 		UncheckedExtrinsic = UncheckedExtrinsic,
 	{
 		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
 		TemplateModule: pallet_template::{Pallet, Call, Storage, Event<T>},
 	}
 );
@@ -30,7 +31,7 @@ impl system::Config for Test {
 	type BaseCallFilter = ();
 	type BlockWeights = ();
 	type BlockLength = ();
-	type DbWeight = ();
+	type DbWeight = RocksDbWeight;
 	type Origin = Origin;
 	type Call = Call;
 	type Index = u64;
@@ -44,7 +45,7 @@ impl system::Config for Test {
 	type BlockHashCount = BlockHashCount;
 	type Version = ();
 	type PalletInfo = PalletInfo;
-	type AccountData = ();
+	type AccountData = pallet_balances::AccountData<u64>;
 	type OnNewAccount = ();
 	type OnKilledAccount = ();
 	type SystemWeightInfo = ();
@@ -52,11 +53,41 @@ impl system::Config for Test {
 	type OnSetCode = ();
 }
 
+parameter_types! {
+	pub const ExistentialDeposit: u64 = 1;
+}
+
+impl pallet_balances::Config for Test {
+	type MaxLocks = ();
+	type Balance = u64;
+	type Event = Event;
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub const FreeCallCooldown: u64 = 10;
+	pub const FreeCallMinBalance: u64 = 10;
+}
+
 impl pallet_template::Config for Test {
 	type Event = Event;
+	type FreeCallCooldown = FreeCallCooldown;
+	type Currency = Balances;
+	type FreeCallMinBalance = FreeCallMinBalance;
 }
 
-// Build genesis storage according to the mock runtime.
+/// An account holding less than `FreeCallMinBalance`.
+pub const UNFUNDED: u64 = 3;
+
+// Build genesis storage according to the mock runtime, with accounts 1 and 2 holding
+// `FreeCallMinBalance` and `UNFUNDED` holding less.
 pub fn new_test_ext() -> sp_io::TestExternalities {
-	system::GenesisConfig::default().build_storage::<Test>().unwrap().into()
+	let mut t = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	pallet_balances::GenesisConfig::<Test> {
+		balances: vec![(1, 10), (2, 10), (UNFUNDED, 9)],
+	}.assimilate_storage(&mut t).unwrap();
+	t.into()
 }
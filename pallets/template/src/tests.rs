@@ -1,5 +1,6 @@
-use crate::{Error, mock::*};
-use frame_support::{assert_ok, assert_noop};
+use crate::{CheckFreeCall, Error, mock::*};
+use frame_support::{assert_ok, assert_noop, traits::Get, weights::GetDispatchInfo};
+use sp_runtime::traits::SignedExtension;
 
 #[test]
 fn it_works_for_default_value() {
@@ -21,3 +22,110 @@ fn correct_error_for_none_value() {
 		);
 	});
 }
+
+fn validate(who: u64, call: &Call) -> Vec<Vec<u8>> {
+	CheckFreeCall::<Test>::new()
+		.validate(&who, call, &call.get_dispatch_info(), 0)
+		.unwrap()
+		.provides
+}
+
+fn pre_dispatch(who: u64, call: &Call) {
+	CheckFreeCall::<Test>::new()
+		.pre_dispatch(&who, call, &call.get_dispatch_info(), 0)
+		.unwrap();
+}
+
+#[test]
+fn first_call_is_free() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let call = Call::TemplateModule(crate::Call::do_something(42));
+
+		assert!(TemplateModule::is_free_call(&1, &call));
+		assert_eq!(validate(1, &call).len(), 1);
+
+		pre_dispatch(1, &call);
+
+		assert_eq!(TemplateModule::last_free_call(1), Some(1));
+		assert!(!TemplateModule::is_free_call(&1, &call));
+		assert!(validate(1, &call).is_empty());
+		// Other accounts are not affected.
+		assert!(TemplateModule::is_free_call(&2, &call));
+	});
+}
+
+#[test]
+fn free_calls_are_rate_limited() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let call = Call::TemplateModule(crate::Call::do_something(42));
+		pre_dispatch(1, &call);
+
+		System::set_block_number(1 + FreeCallCooldown::get() - 1);
+		assert!(!TemplateModule::is_free_call(&1, &call));
+
+		System::set_block_number(1 + FreeCallCooldown::get());
+		assert!(TemplateModule::is_free_call(&1, &call));
+		pre_dispatch(1, &call);
+		assert_eq!(TemplateModule::last_free_call(1), Some(1 + FreeCallCooldown::get()));
+	});
+}
+
+#[test]
+fn free_calls_provide_a_tag_per_account() {
+	new_test_ext().execute_with(|| {
+		let call = Call::TemplateModule(crate::Call::do_something(42));
+
+		assert_eq!(validate(1, &call), validate(1, &call));
+		assert_ne!(validate(1, &call), validate(2, &call));
+	});
+}
+
+#[test]
+fn other_calls_are_not_free() {
+	new_test_ext().execute_with(|| {
+		let call = Call::TemplateModule(crate::Call::cause_error());
+
+		assert!(!TemplateModule::is_free_call(&1, &call));
+		assert!(validate(1, &call).is_empty());
+
+		pre_dispatch(1, &call);
+		assert_eq!(TemplateModule::last_free_call(1), None);
+	});
+}
+
+#[test]
+fn accounts_below_the_minimum_balance_pay_for_their_calls() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let call = Call::TemplateModule(crate::Call::do_something(42));
+
+		assert!(!TemplateModule::is_free_call(&UNFUNDED, &call));
+		assert!(validate(UNFUNDED, &call).is_empty());
+
+		pre_dispatch(UNFUNDED, &call);
+		assert_eq!(TemplateModule::last_free_call(UNFUNDED), None);
+	});
+}
+
+#[test]
+fn free_call_checks_are_added_to_the_block_weight() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let db_weight = <Test as frame_system::Config>::DbWeight::get();
+		let call = Call::TemplateModule(crate::Call::do_something(42));
+
+		// A free call reads the balance and `LastFreeCall`, and writes `LastFreeCall`.
+		pre_dispatch(1, &call);
+		assert_eq!(System::block_weight().total(), db_weight.reads_writes(2, 1));
+
+		// A paid one only reads them.
+		pre_dispatch(1, &call);
+		assert_eq!(System::block_weight().total(), db_weight.reads_writes(4, 1));
+
+		// Other calls check nothing.
+		pre_dispatch(1, &Call::TemplateModule(crate::Call::cause_error()));
+		assert_eq!(System::block_weight().total(), db_weight.reads_writes(4, 1));
+	});
+}
@@ -0,0 +1,36 @@
+//! Runtime APIs the node queries through its custom RPCs.
+
+use codec::Codec;
+use pallet_identity::Data;
+use sp_std::vec::Vec;
+use crate::{AccountId, Identity};
+
+sp_api::decl_runtime_apis! {
+	/// Resolves accounts into the names of their on-chain identities.
+	pub trait IdentityApi<AccountId> where AccountId: Codec {
+		/// The raw display name of `account`, as `parent/sub` for sub-accounts. `None` if the
+		/// account has no identity, or its display name is not raw data.
+		fn display_name(account: AccountId) -> Option<Vec<u8>>;
+	}
+}
+
+fn raw(data: &Data) -> Option<Vec<u8>> {
+	match data {
+		Data::Raw(raw) => Some(raw.clone()),
+		_ => None,
+	}
+}
+
+/// The display name [`IdentityApi::display_name`] resolves `account` into.
+pub fn display_name(account: &AccountId) -> Option<Vec<u8>> {
+	if let Some(registration) = Identity::identity(account) {
+		return raw(&registration.info.display)
+	}
+
+	let (parent, sub_name) = Identity::super_of(account)?;
+	let parent_name = display_name(&parent)?;
+	Some(match raw(&sub_name) {
+		Some(sub_name) => [parent_name, b"/".to_vec(), sub_name].concat(),
+		None => parent_name,
+	})
+}
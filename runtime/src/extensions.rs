@@ -0,0 +1,223 @@
+//! Signed extensions specific to this runtime.
+
+use codec::{Decode, Encode};
+use frame_support::weights::{constants::ExtrinsicBaseWeight, Weight};
+use pallet_transaction_payment::ChargeTransactionPayment;
+use sp_runtime::{
+	traits::{DispatchInfoOf, PostDispatchInfoOf, SaturatedConversion, SignedExtension},
+	transaction_validity::{
+		TransactionPriority, TransactionValidity, TransactionValidityError, ValidTransaction,
+	},
+	DispatchResult, RuntimeDebug,
+};
+use crate::{AccountId, Balance, Call, Runtime, TemplateModule, TransactionPayment};
+
+/// How many times a unit of tip counts towards priority compared to a unit of fee.
+pub const TIP_PRIORITY_FACTOR: Balance = 2;
+
+/// The priority of a transaction paying `fee` and tipping `tip` for a dispatch of `weight`.
+///
+/// This is the amount paid per `ExtrinsicBaseWeight`, where the tip counts
+/// `TIP_PRIORITY_FACTOR` times. Heavy transactions thus need to pay proportionally more to be
+/// included as quickly as light ones.
+pub fn priority(fee: Balance, tip: Balance, weight: Weight) -> TransactionPriority {
+	let paid = fee.saturating_add(tip.saturating_mul(TIP_PRIORITY_FACTOR));
+	let per_weight = paid.saturating_mul(ExtrinsicBaseWeight::get().into()) / Balance::from(weight.max(1));
+	per_weight.saturated_into()
+}
+
+/// Charges transaction fees through `pallet_transaction_payment`, and prioritizes transactions
+/// by the fee and tip they pay per weight.
+///
+/// Calls made free by `pallet_template::CheckFreeCall` are neither charged a fee nor a tip, and
+/// have the lowest priority.
+///
+/// The encoding and identifier are those of `ChargeTransactionPayment`, so that wallets keep
+/// treating the field as the tip.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug)]
+pub struct ChargeTransactionPaymentByTip(#[codec(compact)] Balance);
+
+impl ChargeTransactionPaymentByTip {
+	/// Utility constructor. Used only in client/factory code.
+	pub fn from(tip: Balance) -> Self {
+		Self(tip)
+	}
+
+	fn charge(&self) -> ChargeTransactionPayment<Runtime> {
+		ChargeTransactionPayment::from(self.0)
+	}
+}
+
+impl SignedExtension for ChargeTransactionPaymentByTip {
+	const IDENTIFIER: &'static str = "ChargeTransactionPayment";
+	type AccountId = AccountId;
+	type Call = Call;
+	type AdditionalSigned = ();
+	type Pre = <ChargeTransactionPayment<Runtime> as SignedExtension>::Pre;
+
+	fn additional_signed(&self) -> Result<(), TransactionValidityError> {
+		Ok(())
+	}
+
+	fn validate(
+		&self,
+		who: &Self::AccountId,
+		call: &Self::Call,
+		info: &DispatchInfoOf<Self::Call>,
+		len: usize,
+	) -> TransactionValidity {
+		if TemplateModule::is_free_call(who, call) {
+			return Ok(ValidTransaction::default())
+		}
+
+		let valid = self.charge().validate(who, call, info, len)?;
+		let fee = TransactionPayment::compute_fee(len as u32, info, 0);
+
+		Ok(ValidTransaction {
+			priority: priority(fee, self.0, info.weight),
+			..valid
+		})
+	}
+
+	fn pre_dispatch(
+		self,
+		who: &Self::AccountId,
+		call: &Self::Call,
+		info: &DispatchInfoOf<Self::Call>,
+		len: usize,
+	) -> Result<Self::Pre, TransactionValidityError> {
+		if TemplateModule::is_free_call(who, call) {
+			// Nothing was withdrawn, so nothing is corrected or deposited after dispatch.
+			return Ok((0, who.clone(), Default::default()))
+		}
+
+		self.charge().pre_dispatch(who, call, info, len)
+	}
+
+	fn post_dispatch(
+		pre: Self::Pre,
+		info: &DispatchInfoOf<Self::Call>,
+		post_info: &PostDispatchInfoOf<Self::Call>,
+		len: usize,
+		result: &DispatchResult,
+	) -> Result<(), TransactionValidityError> {
+		ChargeTransactionPayment::<Runtime>::post_dispatch(pre, info, post_info, len, result)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{mock::*, Balances, BalancesCall, Executive, FreeCallMinBalance, CENTS, UNITS};
+	use frame_support::{traits::{Currency, Get}, weights::GetDispatchInfo};
+	use sp_keyring::Sr25519Keyring::{Alice, Bob, Dave};
+	use sp_runtime::transaction_validity::InvalidTransaction;
+
+	fn validate(tip: Balance, call: &Call) -> TransactionPriority {
+		ChargeTransactionPaymentByTip::from(tip)
+			.validate(&Alice.to_account_id(), call, &call.get_dispatch_info(), 100)
+			.unwrap()
+			.priority
+	}
+
+	#[test]
+	fn higher_tips_have_higher_priority() {
+		new_test_ext().execute_with(|| {
+			let remark = Call::System(frame_system::Call::remark(vec![]));
+
+			let untipped = validate(0, &remark);
+			let tipped = validate(CENTS, &remark);
+			let generously_tipped = validate(10 * CENTS, &remark);
+
+			assert!(untipped > 0);
+			assert!(untipped < tipped);
+			assert!(tipped < generously_tipped);
+		});
+	}
+
+	#[test]
+	fn heavier_calls_need_higher_tips() {
+		new_test_ext().execute_with(|| {
+			let remark = Call::System(frame_system::Call::remark(vec![]));
+			let transfer = Call::Balances(BalancesCall::transfer(Bob.to_account_id().into(), UNITS));
+			assert!(transfer.get_dispatch_info().weight > remark.get_dispatch_info().weight);
+
+			assert!(validate(CENTS, &transfer) < validate(CENTS, &remark));
+		});
+	}
+
+	#[test]
+	fn priority_is_paid_per_weight() {
+		let base = ExtrinsicBaseWeight::get();
+
+		assert_eq!(priority(100, 0, base), 100);
+		assert_eq!(priority(100, 0, 2 * base), 50);
+		assert_eq!(priority(100, 50, base), 100 + 50 * TIP_PRIORITY_FACTOR as u64);
+		assert_eq!(priority(Balance::max_value(), 0, 0), TransactionPriority::max_value());
+	}
+
+	#[test]
+	fn first_call_is_free_above_the_minimum_balance() {
+		new_test_ext().execute_with(|| {
+			initialize_block(1);
+			let min_balance = FreeCallMinBalance::get();
+			Balances::make_free_balance_be(&Dave.to_account_id(), min_balance);
+
+			let call = Call::TemplateModule(pallet_template::Call::do_something(42));
+			assert_eq!(Executive::apply_extrinsic(sign(Dave, call.clone(), 0, 0)), Ok(Ok(())));
+			assert_eq!(TemplateModule::something(), Some(42));
+			assert_eq!(Balances::free_balance(Dave.to_account_id()), min_balance);
+
+			// The minimum balance doesn't cover the fee of the next call.
+			assert_eq!(
+				Executive::apply_extrinsic(sign(Dave, call, 1, 0)),
+				Err(InvalidTransaction::Payment.into()),
+			);
+		});
+	}
+
+	#[test]
+	fn unfunded_accounts_pay_for_their_first_call() {
+		new_test_ext().execute_with(|| {
+			initialize_block(1);
+			assert_eq!(Balances::free_balance(Dave.to_account_id()), 0);
+
+			let call = Call::TemplateModule(pallet_template::Call::do_something(42));
+			assert_eq!(
+				Executive::apply_extrinsic(sign(Dave, call, 0, 0)),
+				Err(InvalidTransaction::Payment.into()),
+			);
+			assert_eq!(TemplateModule::something(), None);
+		});
+	}
+
+	#[test]
+	fn free_calls_pay_no_fee_nor_tip() {
+		new_test_ext().execute_with(|| {
+			initialize_block(1);
+			let call = Call::TemplateModule(pallet_template::Call::do_something(42));
+
+			assert_eq!(Executive::apply_extrinsic(sign(Bob, call.clone(), 0, UNITS)), Ok(Ok(())));
+			assert_eq!(Balances::free_balance(Bob.to_account_id()), INITIAL_BALANCE);
+
+			// The next call within the cooldown is charged.
+			assert_eq!(Executive::apply_extrinsic(sign(Bob, call, 1, 0)), Ok(Ok(())));
+			assert!(Balances::free_balance(Bob.to_account_id()) < INITIAL_BALANCE);
+		});
+	}
+
+	#[test]
+	fn pool_rejects_unpaid_calls_after_the_free_one() {
+		new_test_ext().execute_with(|| {
+			initialize_block(1);
+			let call = Call::TemplateModule(pallet_template::Call::do_something(42));
+			let validate = |call: &Call| ChargeTransactionPaymentByTip::from(0)
+				.validate(&Dave.to_account_id(), call, &call.get_dispatch_info(), 100);
+			Balances::make_free_balance_be(&Dave.to_account_id(), FreeCallMinBalance::get());
+
+			assert!(validate(&call).is_ok());
+			assert_eq!(Executive::apply_extrinsic(sign(Dave, call.clone(), 0, 0)), Ok(Ok(())));
+			assert_eq!(validate(&call), Err(InvalidTransaction::Payment.into()));
+		});
+	}
+}
@@ -0,0 +1,280 @@
+//! Some configurable implementations as associated type for the runtime.
+
+use codec::{Decode, Encode};
+use frame_support::{
+	traits::{Currency, FindAuthor, Imbalance, OnUnbalanced},
+	weights::{
+		constants::ExtrinsicBaseWeight, WeightToFeeCoefficient, WeightToFeeCoefficients,
+		WeightToFeePolynomial,
+	},
+};
+use pallet_aura::FindAccountFromAuthorIndex;
+use smallvec::smallvec;
+use sp_runtime::{ConsensusEngineId, Perbill};
+use crate::{AccountId, Aura, Balance, Balances, Runtime, System, Treasury, CENTS};
+
+type NegativeImbalance = <Balances as Currency<AccountId>>::NegativeImbalance;
+
+/// Finds the account of the Aura authority which authored the block.
+///
+/// Aura authorities are sr25519 keys, which double as the `AccountId` of their owner.
+pub struct AuraAccountAdapter;
+
+impl FindAuthor<AccountId> for AuraAccountAdapter {
+	fn find_author<'a, I>(digests: I) -> Option<AccountId>
+		where I: 'a + IntoIterator<Item=(ConsensusEngineId, &'a [u8])>
+	{
+		FindAccountFromAuthorIndex::<Runtime, Aura>::find_author(digests)
+			.and_then(|authority| AccountId::decode(&mut &authority.encode()[..]).ok())
+	}
+}
+
+/// Credits the author of the current block, or the treasury if it can't be found.
+pub struct ToAuthor;
+
+impl OnUnbalanced<NegativeImbalance> for ToAuthor {
+	fn on_nonzero_unbalanced(amount: NegativeImbalance) {
+		let digest = System::digest();
+		let pre_runtime_digests = digest.logs.iter().filter_map(|d| d.as_pre_runtime());
+
+		match AuraAccountAdapter::find_author(pre_runtime_digests) {
+			Some(author) => Balances::resolve_creating(&author, amount),
+			None => Treasury::on_unbalanced(amount),
+		}
+	}
+}
+
+/// Splits transaction fees and tips between the treasury (80%) and the block author (20%).
+pub struct DealWithFees;
+
+impl OnUnbalanced<NegativeImbalance> for DealWithFees {
+	fn on_unbalanceds<B>(mut fees_then_tips: impl Iterator<Item=NegativeImbalance>) {
+		if let Some(mut fees) = fees_then_tips.next() {
+			if let Some(tips) = fees_then_tips.next() {
+				tips.merge_into(&mut fees);
+			}
+			let (to_treasury, to_author) = fees.ration(80, 20);
+			Treasury::on_unbalanced(to_treasury);
+			ToAuthor::on_unbalanced(to_author);
+		}
+	}
+}
+
+/// Converts weight to fee, so that an extrinsic of `ExtrinsicBaseWeight` costs a tenth of a
+/// cent. The fee grows linearly with the weight.
+pub struct WeightToFee;
+
+impl WeightToFeePolynomial for WeightToFee {
+	type Balance = Balance;
+
+	fn polynomial() -> WeightToFeeCoefficients<Self::Balance> {
+		let p = CENTS / 10;
+		let q = Balance::from(ExtrinsicBaseWeight::get());
+		smallvec![WeightToFeeCoefficient {
+			degree: 1,
+			negative: false,
+			coeff_frac: Perbill::from_rational(p % q, q),
+			coeff_integer: p / q,
+		}]
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{mock::*, BalancesCall, Call, Executive, UNITS};
+	use sp_keyring::Sr25519Keyring::{Alice, Bob, Charlie};
+
+	#[test]
+	fn fees_are_split_between_treasury_and_author() {
+		new_test_ext().execute_with(|| {
+			initialize_block(1);
+			let total_issuance = Balances::total_issuance();
+
+			for nonce in 0..5 {
+				let transfer = Call::Balances(
+					BalancesCall::transfer(Charlie.to_account_id().into(), UNITS)
+				);
+				assert_eq!(Executive::apply_extrinsic(sign(Bob, transfer, nonce, 0)), Ok(Ok(())));
+			}
+
+			let fees = INITIAL_BALANCE - 5 * UNITS - Balances::free_balance(Bob.to_account_id());
+			let to_treasury = Balances::free_balance(Treasury::account_id());
+			let to_author = Balances::free_balance(Alice.to_account_id()) - INITIAL_BALANCE;
+
+			// Nothing is burned, every fee ends up with the treasury or the author.
+			assert!(fees > 0);
+			assert_eq!(Balances::total_issuance(), total_issuance);
+			assert_eq!(to_treasury + to_author, fees);
+			// Each fee is rationed separately, so allow one unit of rounding per transaction.
+			assert!(to_treasury.max(fees * 4 / 5) - to_treasury.min(fees * 4 / 5) <= 5);
+		});
+	}
+
+	#[test]
+	fn tips_follow_fees() {
+		new_test_ext().execute_with(|| {
+			initialize_block(1);
+			let remark = Call::System(frame_system::Call::remark(vec![]));
+
+			// Both tips have the same encoded length, so the fees only differ by the tip.
+			assert_eq!(Executive::apply_extrinsic(sign(Bob, remark.clone(), 0, 2 * UNITS)), Ok(Ok(())));
+			let small_tip = Balances::free_balance(Treasury::account_id());
+			assert_eq!(Executive::apply_extrinsic(sign(Bob, remark, 1, 12 * UNITS)), Ok(Ok(())));
+			let large_tip = Balances::free_balance(Treasury::account_id()) - small_tip;
+
+			assert_eq!(large_tip - small_tip, 8 * UNITS);
+		});
+	}
+
+	#[test]
+	fn fees_go_to_treasury_without_author() {
+		new_test_ext().execute_with(|| {
+			let fee = Balances::issue(1_000);
+			DealWithFees::on_unbalanceds(vec![fee].into_iter());
+
+			assert_eq!(Balances::free_balance(Treasury::account_id()), 1_000);
+		});
+	}
+}
+
+#[cfg(test)]
+mod multiplier_tests {
+	use crate::{
+		mock::*, AdjustmentVariable, BlockWeights, MinimumMultiplier, Runtime, System,
+		TargetBlockFullness, TransactionPayment,
+	};
+	use frame_support::{traits::OnFinalize, weights::{DispatchClass, Weight}};
+	use pallet_transaction_payment::{Multiplier, TargetedFeeAdjustment};
+	use sp_runtime::{traits::Convert, FixedPointNumber};
+
+	type FeeMultiplierUpdate =
+		TargetedFeeAdjustment<Runtime, TargetBlockFullness, AdjustmentVariable, MinimumMultiplier>;
+
+	/// The weight of a block filled up to `NORMAL_DISPATCH_RATIO`.
+	fn max_normal() -> Weight {
+		let block_weights = BlockWeights::get();
+		block_weights.get(DispatchClass::Normal).max_total.unwrap_or(block_weights.max_block)
+	}
+
+	#[test]
+	fn multiplier_climbs_on_full_blocks_and_then_decays() {
+		new_test_ext().execute_with(|| {
+			let mut multiplier = TransactionPayment::next_fee_multiplier();
+			assert_eq!(multiplier, Multiplier::saturating_from_integer(1));
+
+			for block in 1..=100 {
+				System::set_block_consumed_resources(max_normal(), 0);
+				TransactionPayment::on_finalize(block);
+
+				let next = TransactionPayment::next_fee_multiplier();
+				assert!(next > multiplier, "{:?} !> {:?} at block {}", next, multiplier, block);
+				multiplier = next;
+			}
+
+			for block in 101..=200 {
+				System::set_block_consumed_resources(0, 0);
+				TransactionPayment::on_finalize(block);
+
+				let next = TransactionPayment::next_fee_multiplier();
+				assert!(next < multiplier, "{:?} !< {:?} at block {}", next, multiplier, block);
+				multiplier = next;
+			}
+		});
+	}
+
+	#[test]
+	fn multiplier_is_stable_at_target_fullness() {
+		new_test_ext().execute_with(|| {
+			let target = TargetBlockFullness::get() * max_normal();
+			System::set_block_consumed_resources(target, 0);
+
+			let multiplier = Multiplier::saturating_from_integer(1);
+			assert_eq!(FeeMultiplierUpdate::convert(multiplier), multiplier);
+		});
+	}
+
+	#[test]
+	fn multiplier_recovers_from_minimum() {
+		new_test_ext().execute_with(|| {
+			System::set_block_consumed_resources(max_normal(), 0);
+
+			let mut multiplier = MinimumMultiplier::get();
+			for _ in 0..10 {
+				let next = FeeMultiplierUpdate::convert(multiplier);
+				assert!(next > multiplier);
+				multiplier = next;
+			}
+		});
+	}
+
+	#[test]
+	fn multiplier_never_drops_below_minimum() {
+		new_test_ext().execute_with(|| {
+			System::set_block_consumed_resources(0, 0);
+
+			let minimum = MinimumMultiplier::get();
+			assert_eq!(FeeMultiplierUpdate::convert(minimum), minimum);
+		});
+	}
+}
+
+#[cfg(test)]
+mod fee_tests {
+	use super::WeightToFee;
+	use crate::{
+		mock::*, BalancesCall, Call, TransactionByteFee, TransactionPayment, CENTS, UNITS,
+	};
+	use codec::Encode;
+	use frame_support::{
+		traits::Get,
+		weights::{constants::{ExtrinsicBaseWeight, RocksDbWeight}, WeightToFeePolynomial},
+	};
+	use pallet_balances::WeightInfo;
+	use sp_keyring::Sr25519Keyring::{Alice, Bob};
+
+	#[test]
+	fn base_extrinsic_costs_a_tenth_of_a_cent() {
+		assert_eq!(WeightToFee::calc(&ExtrinsicBaseWeight::get()), CENTS / 10);
+	}
+
+	#[test]
+	fn fee_details_match_table() {
+		type BalancesWeight = pallet_balances::weights::SubstrateWeight<crate::Runtime>;
+
+		// (call, weight fee at a multiplier of one, bounds of the whole inclusion fee)
+		let table = vec![
+			(
+				Call::Balances(BalancesCall::transfer(Bob.to_account_id().into(), UNITS)),
+				WeightToFee::calc(&BalancesWeight::transfer()),
+				(CENTS, 3 * CENTS),
+			),
+			(
+				Call::TemplateModule(pallet_template::Call::do_something(42)),
+				WeightToFee::calc(&(10_000 + RocksDbWeight::get().writes(1))),
+				(CENTS, 2 * CENTS),
+			),
+		];
+
+		new_test_ext().execute_with(|| {
+			initialize_block(1);
+
+			for (call, weight_fee, (min_fee, max_fee)) in table {
+				let uxt = sign(Alice, call.clone(), 0, 0);
+				let len = uxt.encode().len() as u32;
+				let details = TransactionPayment::query_fee_details(uxt, len);
+				let inclusion_fee = details.inclusion_fee.expect("signed calls pay a fee");
+
+				assert_eq!(inclusion_fee.base_fee, CENTS / 10, "{:?}", call);
+				assert_eq!(inclusion_fee.len_fee, len as u128 * TransactionByteFee::get(), "{:?}", call);
+				assert_eq!(inclusion_fee.adjusted_weight_fee, weight_fee, "{:?}", call);
+				assert_eq!(details.tip, 0);
+
+				let total = inclusion_fee.base_fee
+					+ inclusion_fee.len_fee
+					+ inclusion_fee.adjusted_weight_fee;
+				assert!(min_fee <= total && total <= max_fee, "{:?} costs {}", call, total);
+			}
+		});
+	}
+}
@@ -13,7 +13,7 @@ use sp_runtime::{
 	transaction_validity::{TransactionValidity, TransactionSource},
 };
 use sp_runtime::traits::{
-	BlakeTwo256, Block as BlockT, AccountIdLookup, Verify, IdentifyAccount, NumberFor,
+	BlakeTwo256, Block as BlockT, Verify, IdentifyAccount, NumberFor,
 };
 use sp_api::impl_runtime_apis;
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
@@ -28,20 +28,35 @@ use sp_version::NativeVersion;
 pub use sp_runtime::BuildStorage;
 pub use pallet_timestamp::Call as TimestampCall;
 pub use pallet_balances::Call as BalancesCall;
-pub use sp_runtime::{Permill, Perbill};
+pub use sp_runtime::{Permill, Perbill, Perquintill, FixedPointNumber, FixedU128};
 pub use frame_support::{
 	construct_runtime, parameter_types, StorageValue,
 	traits::{KeyOwnerProofSystem, Randomness},
 	weights::{
-		Weight, IdentityFee,
+		Weight,
 		constants::{BlockExecutionWeight, ExtrinsicBaseWeight, RocksDbWeight, WEIGHT_PER_SECOND},
 	},
 };
-use pallet_transaction_payment::CurrencyAdapter;
+use pallet_transaction_payment::{CurrencyAdapter, Multiplier, TargetedFeeAdjustment};
+use frame_system::EnsureRoot;
+use frame_support::PalletId;
 
 /// Import the template pallet.
 pub use pallet_template;
 
+/// Implementations of some helper traits passed into runtime modules as associated types.
+pub mod impls;
+use impls::{AuraAccountAdapter, DealWithFees, WeightToFee};
+
+/// Signed extensions specific to this runtime.
+pub mod extensions;
+
+/// Runtime APIs specific to this runtime.
+pub mod apis;
+
+#[cfg(test)]
+mod mock;
+
 /// An index to a block.
 pub type BlockNumber = u32;
 
@@ -62,6 +77,9 @@ pub type Balance = u128;
 /// Index of a transaction in the chain.
 pub type Index = u32;
 
+/// Identifier of an asset of pallet-assets.
+pub type AssetId = u32;
+
 /// A hash of some data used by the chain.
 pub type Hash = sp_core::H256;
 
@@ -107,10 +125,12 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	//   `spec_version`, and `authoring_version` are the same between Wasm and native.
 	// This value is set to 100 to notify Polkadot-JS App (https://polkadot.js.org/apps) to use
 	//   the compatible custom types.
-	spec_version: 100,
+	spec_version: 101,
 	impl_version: 1,
 	apis: RUNTIME_API_VERSIONS,
-	transaction_version: 1,
+	// Bumped whenever the format of extrinsics changes, e.g. their address or signed extensions,
+	//   so that external signers notice.
+	transaction_version: 2,
 };
 
 /// This determines the average expected block time that we are targeting.
@@ -130,6 +150,11 @@ pub const MINUTES: BlockNumber = 60_000 / (MILLISECS_PER_BLOCK as BlockNumber);
 pub const HOURS: BlockNumber = MINUTES * 60;
 pub const DAYS: BlockNumber = HOURS * 24;
 
+// Balances are expressed in units of the native token, which has 12 decimals.
+pub const UNITS: Balance = 1_000_000_000_000;
+pub const CENTS: Balance = UNITS / 100;
+pub const MILLICENTS: Balance = CENTS / 1_000;
+
 /// The version information used to identify this runtime when compiled natively.
 #[cfg(feature = "std")]
 pub fn native_version() -> NativeVersion {
@@ -166,7 +191,7 @@ impl frame_system::Config for Runtime {
 	/// The aggregated dispatch type that is available for extrinsics.
 	type Call = Call;
 	/// The lookup mechanism to get account ID from whatever is passed in dispatchers.
-	type Lookup = AccountIdLookup<AccountId, ()>;
+	type Lookup = Indices;
 	/// The index type for storing how many extrinsics an account has signed.
 	type Index = Index;
 	/// The index type for blocks.
@@ -228,6 +253,29 @@ impl pallet_grandpa::Config for Runtime {
 	type WeightInfo = ();
 }
 
+parameter_types! {
+	pub const UncleGenerations: BlockNumber = 0;
+}
+
+impl pallet_authorship::Config for Runtime {
+	/// Resolves the author through `FindAccountFromAuthorIndex<Runtime, Aura>`.
+	type FindAuthor = AuraAccountAdapter;
+	/// Aura has no uncles.
+	type UncleGenerations = UncleGenerations;
+	type FilterUncle = ();
+	/// Rewards the author of each block.
+	type EventHandler = Rewards;
+}
+
+/// Configure the block author rewards in pallets/rewards.
+impl pallet_rewards::Config for Runtime {
+	type Event = Event;
+	type Currency = Balances;
+	/// Every block is rewarded with the same amount. Use `pallet_rewards::Halving` for a
+	/// decreasing reward.
+	type RewardCurve = ();
+}
+
 parameter_types! {
 	pub const MinimumPeriod: u64 = SLOT_DURATION / 2;
 }
@@ -240,6 +288,21 @@ impl pallet_timestamp::Config for Runtime {
 	type WeightInfo = ();
 }
 
+parameter_types! {
+	pub const IndexDeposit: Balance = UNITS;
+}
+
+impl pallet_indices::Config for Runtime {
+	/// The type for recording indexing into the account enumeration.
+	type AccountIndex = AccountIndex;
+	type Currency = Balances;
+	/// The deposit reserved when claiming an index.
+	type Deposit = IndexDeposit;
+	/// The ubiquitous event type.
+	type Event = Event;
+	type WeightInfo = pallet_indices::weights::SubstrateWeight<Runtime>;
+}
+
 parameter_types! {
 	pub const ExistentialDeposit: u128 = 500;
 	pub const MaxLocks: u32 = 50;
@@ -258,14 +321,25 @@ impl pallet_balances::Config for Runtime {
 }
 
 parameter_types! {
-	pub const TransactionByteFee: Balance = 1;
+	pub const TransactionByteFee: Balance = 10 * MILLICENTS;
+	/// The portion of the normal dispatch class the fee multiplier aims for. Blocks filled
+	/// less than this decrease the multiplier, blocks filled more increase it.
+	pub const TargetBlockFullness: Perquintill = Perquintill::from_percent(25);
+	/// How fast the multiplier reacts to blocks deviating from `TargetBlockFullness`.
+	pub AdjustmentVariable: Multiplier = Multiplier::saturating_from_rational(3, 100_000);
+	/// The floor of the multiplier. Combined with `AdjustmentVariable`, full blocks must still
+	/// be able to raise the multiplier from this value.
+	pub MinimumMultiplier: Multiplier = Multiplier::saturating_from_rational(1, 1_000_000_000u128);
 }
 
 impl pallet_transaction_payment::Config for Runtime {
-	type OnChargeTransaction = CurrencyAdapter<Balances, ()>;
+	/// Fees are paid in the native currency, unless the payer chose an asset.
+	type OnChargeTransaction =
+		pallet_asset_fees::AssetOrNativeAdapter<Self, CurrencyAdapter<Balances, DealWithFees>>;
 	type TransactionByteFee = TransactionByteFee;
-	type WeightToFee = IdentityFee<Balance>;
-	type FeeMultiplierUpdate = ();
+	type WeightToFee = WeightToFee;
+	type FeeMultiplierUpdate =
+		TargetedFeeAdjustment<Self, TargetBlockFullness, AdjustmentVariable, MinimumMultiplier>;
 }
 
 impl pallet_sudo::Config for Runtime {
@@ -273,9 +347,189 @@ impl pallet_sudo::Config for Runtime {
 	type Call = Call;
 }
 
+parameter_types! {
+	pub const ProposalBond: Permill = Permill::from_percent(5);
+	pub const ProposalBondMinimum: Balance = UNITS;
+	pub const SpendPeriod: BlockNumber = DAYS;
+	pub const Burn: Permill = Permill::from_percent(0);
+	pub const TreasuryPalletId: PalletId = PalletId(*b"py/trsry");
+	pub const MaxApprovals: u32 = 100;
+}
+
+impl pallet_treasury::Config for Runtime {
+	/// The treasury's pallet id, used for deriving its sovereign account ID.
+	type PalletId = TreasuryPalletId;
+	type Currency = Balances;
+	/// Spend proposals are approved or rejected through sudo.
+	type ApproveOrigin = EnsureRoot<AccountId>;
+	type RejectOrigin = EnsureRoot<AccountId>;
+	type Event = Event;
+	/// Slashed proposal bonds stay in the treasury.
+	type OnSlash = Treasury;
+	type ProposalBond = ProposalBond;
+	type ProposalBondMinimum = ProposalBondMinimum;
+	type SpendPeriod = SpendPeriod;
+	type Burn = Burn;
+	type BurnDestination = ();
+	type SpendFunds = ();
+	type WeightInfo = pallet_treasury::weights::SubstrateWeight<Runtime>;
+	type MaxApprovals = MaxApprovals;
+}
+
+parameter_types! {
+	pub const BasicDeposit: Balance = 10 * UNITS;
+	pub const FieldDeposit: Balance = UNITS / 4;
+	pub const SubAccountDeposit: Balance = 2 * UNITS;
+	pub const MaxSubAccounts: u32 = 100;
+	pub const MaxAdditionalFields: u32 = 100;
+	pub const MaxRegistrars: u32 = 20;
+}
+
+impl pallet_identity::Config for Runtime {
+	type Event = Event;
+	type Currency = Balances;
+	/// The deposit reserved when setting an identity.
+	type BasicDeposit = BasicDeposit;
+	/// The deposit reserved per additional field of an identity.
+	type FieldDeposit = FieldDeposit;
+	/// The deposit reserved per sub-account.
+	type SubAccountDeposit = SubAccountDeposit;
+	type MaxSubAccounts = MaxSubAccounts;
+	type MaxAdditionalFields = MaxAdditionalFields;
+	type MaxRegistrars = MaxRegistrars;
+	type Slashed = Treasury;
+	type ForceOrigin = EnsureRoot<AccountId>;
+	type RegistrarOrigin = EnsureRoot<AccountId>;
+	type WeightInfo = pallet_identity::weights::SubstrateWeight<Runtime>;
+}
+
+/// Configure the registrars added to pallet-identity at genesis.
+impl pallet_registrars::Config for Runtime {}
+
+parameter_types! {
+	pub const AssetDeposit: Balance = 100 * UNITS;
+	pub const ApprovalDeposit: Balance = UNITS;
+	pub const StringLimit: u32 = 50;
+	pub const MetadataDepositBase: Balance = 10 * UNITS;
+	pub const MetadataDepositPerByte: Balance = UNITS;
+}
+
+impl pallet_assets::Config for Runtime {
+	type Event = Event;
+	type Balance = Balance;
+	type AssetId = AssetId;
+	type Currency = Balances;
+	type ForceOrigin = EnsureRoot<AccountId>;
+	/// The deposit reserved to create an asset.
+	type AssetDeposit = AssetDeposit;
+	type MetadataDepositBase = MetadataDepositBase;
+	type MetadataDepositPerByte = MetadataDepositPerByte;
+	/// The deposit reserved per approval of a transfer.
+	type ApprovalDeposit = ApprovalDeposit;
+	type StringLimit = StringLimit;
+	type Freezer = ();
+	type Extra = ();
+	type WeightInfo = pallet_assets::weights::SubstrateWeight<Runtime>;
+}
+
+parameter_types! {
+	pub TreasuryAccount: AccountId = Treasury::account_id();
+	pub const AssetFeeAuthorShare: Perbill = Perbill::from_percent(20);
+}
+
+/// Configure the payment of transaction fees in assets.
+impl pallet_asset_fees::Config for Runtime {
+	type Event = Event;
+	/// Like native fees, fees paid in assets fund the treasury (80%) and the block author (20%),
+	/// see `DealWithFees`.
+	type FeeCollector = TreasuryAccount;
+	type FindAuthor = AuraAccountAdapter;
+	type AuthorShare = AssetFeeAuthorShare;
+}
+
+parameter_types! {
+	pub const NftCollectionDeposit: Balance = 10 * UNITS;
+	pub const NftItemDeposit: Balance = 10 * CENTS;
+	pub const NftMetadataDepositBase: Balance = UNITS;
+	pub const NftMetadataDepositPerByte: Balance = 10 * CENTS;
+	pub const NftStringLimit: u32 = 256;
+}
+
+impl pallet_nft::Config for Runtime {
+	type Event = Event;
+	type Currency = Balances;
+	type CollectionDeposit = NftCollectionDeposit;
+	type ItemDeposit = NftItemDeposit;
+	/// The deposit reserved for the metadata of an item.
+	type MetadataDepositBase = NftMetadataDepositBase;
+	type MetadataDepositPerByte = NftMetadataDepositPerByte;
+	type StringLimit = NftStringLimit;
+	/// Estimated until benchmarked, see `pallet_nft::weights`.
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub const EscrowMinAmount: Balance = 10 * CENTS;
+	pub const EscrowMaxExpiry: BlockNumber = 30 * DAYS;
+	pub const MaxExpiringPerBlock: u32 = 64;
+}
+
+impl pallet_escrow::Config for Runtime {
+	type Event = Event;
+	/// Payments in escrow are reserved from the payer's balance.
+	type Currency = Balances;
+	type MinAmount = EscrowMinAmount;
+	type MaxExpiry = EscrowMaxExpiry;
+	type MaxExpiringPerBlock = MaxExpiringPerBlock;
+	/// Estimated until benchmarked, see `pallet_escrow::weights`.
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub const OracleMaxAge: BlockNumber = 10 * MINUTES;
+	pub const OracleMaxFeeders: u32 = 16;
+}
+
+impl pallet_oracle::Config for Runtime {
+	type Event = Event;
+	/// Feeders submit the price of assets, in the native currency.
+	type OracleKey = AssetId;
+	type OracleValue = FixedU128;
+	type MaxAge = OracleMaxAge;
+	type MaxFeeders = OracleMaxFeeders;
+}
+
+parameter_types! {
+	pub const RandomnessBond: Balance = 100 * UNITS;
+	pub const RandomnessCommitPeriod: BlockNumber = 10 * MINUTES;
+	pub const RandomnessRevealPeriod: BlockNumber = 10 * MINUTES;
+	pub const MaxRandomnessParticipants: u32 = 64;
+}
+
+impl pallet_commit_reveal::Config for Runtime {
+	type Event = Event;
+	type Currency = Balances;
+	/// The bond at stake for not revealing a secret.
+	type Bond = RandomnessBond;
+	type CommitPeriod = RandomnessCommitPeriod;
+	type RevealPeriod = RandomnessRevealPeriod;
+	type MaxParticipants = MaxRandomnessParticipants;
+	type Slash = Treasury;
+}
+
+parameter_types! {
+	pub const FreeCallCooldown: BlockNumber = 7 * DAYS;
+	pub const FreeCallMinBalance: Balance = CENTS;
+}
+
 /// Configure the pallet-template in pallets/template.
 impl pallet_template::Config for Runtime {
 	type Event = Event;
+	/// An account can make a free call once a week.
+	type FreeCallCooldown = FreeCallCooldown;
+	type Currency = Balances;
+	/// An account must hold a cent to make free calls.
+	type FreeCallMinBalance = FreeCallMinBalance;
 }
 
 // Create the runtime by composing the FRAME pallets that were previously configured.
@@ -286,20 +540,31 @@ construct_runtime!(
 		UncheckedExtrinsic = UncheckedExtrinsic
 	{
 		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
-		RandomnessCollectiveFlip: pallet_randomness_collective_flip::{Pallet, Call, Storage},
 		Timestamp: pallet_timestamp::{Pallet, Call, Storage, Inherent},
 		Aura: pallet_aura::{Pallet, Config<T>},
+		Authorship: pallet_authorship::{Pallet, Call, Storage},
 		Grandpa: pallet_grandpa::{Pallet, Call, Storage, Config, Event},
+		Indices: pallet_indices::{Pallet, Call, Storage, Config<T>, Event<T>},
 		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
 		TransactionPayment: pallet_transaction_payment::{Pallet, Storage},
 		Sudo: pallet_sudo::{Pallet, Call, Config<T>, Storage, Event<T>},
+		Treasury: pallet_treasury::{Pallet, Call, Storage, Config, Event<T>},
+		Identity: pallet_identity::{Pallet, Call, Storage, Event<T>},
+		Registrars: pallet_registrars::{Pallet, Config<T>},
+		Rewards: pallet_rewards::{Pallet, Call, Storage, Config<T>, Event<T>},
+		Assets: pallet_assets::{Pallet, Call, Storage, Event<T>},
+		AssetFees: pallet_asset_fees::{Pallet, Call, Storage, Config<T>, Event<T>},
+		Nft: pallet_nft::{Pallet, Call, Storage, Event<T>},
+		Escrow: pallet_escrow::{Pallet, Call, Storage, Event<T>},
+		Oracle: pallet_oracle::{Pallet, Call, Storage, Config<T>, Event<T>},
+		CommitReveal: pallet_commit_reveal::{Pallet, Call, Storage, Event<T>},
 		// Include the custom logic from the pallet-template in the runtime.
 		TemplateModule: pallet_template::{Pallet, Call, Storage, Event<T>},
 	}
 );
 
 /// The address format for describing accounts.
-pub type Address = sp_runtime::MultiAddress<AccountId, ()>;
+pub type Address = sp_runtime::MultiAddress<AccountId, AccountIndex>;
 /// Block header type as expected by this runtime.
 pub type Header = generic::Header<BlockNumber, BlakeTwo256>;
 /// Block type as expected by this runtime.
@@ -316,7 +581,10 @@ pub type SignedExtra = (
 	frame_system::CheckEra<Runtime>,
 	frame_system::CheckNonce<Runtime>,
 	frame_system::CheckWeight<Runtime>,
-	pallet_transaction_payment::ChargeTransactionPayment<Runtime>
+	// Must come before `CheckFreeCall`, which records free calls: coming after, it would charge
+	// every free call.
+	extensions::ChargeTransactionPaymentByTip,
+	pallet_template::CheckFreeCall<Runtime>,
 );
 /// Unchecked extrinsic type as expected by this runtime.
 pub type UncheckedExtrinsic = generic::UncheckedExtrinsic<Address, Call, Signature, SignedExtra>;
@@ -373,7 +641,7 @@ impl_runtime_apis! {
 		}
 
 		fn random_seed() -> <Block as BlockT>::Hash {
-			RandomnessCollectiveFlip::random_seed().0
+			CommitReveal::random_seed().0
 		}
 	}
 
@@ -446,6 +714,12 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl apis::IdentityApi<Block, AccountId> for Runtime {
+		fn display_name(account: AccountId) -> Option<Vec<u8>> {
+			apis::display_name(&account)
+		}
+	}
+
 	impl pallet_transaction_payment_rpc_runtime_api::TransactionPaymentApi<Block, Balance> for Runtime {
 		fn query_info(
 			uxt: <Block as BlockT>::Extrinsic,
@@ -488,8 +762,14 @@ impl_runtime_apis! {
 			let params = (&config, &whitelist);
 
 			add_benchmark!(params, batches, frame_system, SystemBench::<Runtime>);
+			add_benchmark!(params, batches, pallet_assets, Assets);
 			add_benchmark!(params, batches, pallet_balances, Balances);
+			add_benchmark!(params, batches, pallet_escrow, Escrow);
+			add_benchmark!(params, batches, pallet_identity, Identity);
+			add_benchmark!(params, batches, pallet_indices, Indices);
+			add_benchmark!(params, batches, pallet_nft, Nft);
 			add_benchmark!(params, batches, pallet_timestamp, Timestamp);
+			add_benchmark!(params, batches, pallet_treasury, Treasury);
 			add_benchmark!(params, batches, pallet_template, TemplateModule);
 
 			if batches.is_empty() { return Err("Benchmark not found for this pallet.".into()) }
@@ -497,3 +777,91 @@ impl_runtime_apis! {
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::mock::*;
+	use frame_support::assert_ok;
+	use pallet_identity::{Data, IdentityInfo};
+	use sp_keyring::Sr25519Keyring::{Alice, Bob, Charlie};
+	use sp_runtime::{transaction_validity::UnknownTransaction, MultiAddress};
+
+	fn claim_index(index: AccountIndex) {
+		let claim = Call::Indices(pallet_indices::Call::claim(index));
+		assert_eq!(Executive::apply_extrinsic(sign(Charlie, claim, 0, 0)), Ok(Ok(())));
+	}
+
+	#[test]
+	fn transfers_reach_claimed_indices() {
+		new_test_ext().execute_with(|| {
+			initialize_block(1);
+			claim_index(7);
+			assert_eq!(Indices::lookup_index(7), Some(Charlie.to_account_id()));
+			assert_eq!(Balances::reserved_balance(Charlie.to_account_id()), IndexDeposit::get());
+
+			let before = Balances::free_balance(Charlie.to_account_id());
+			let transfer = Call::Balances(BalancesCall::transfer(MultiAddress::Index(7), UNITS));
+			assert_eq!(Executive::apply_extrinsic(sign(Bob, transfer, 0, 0)), Ok(Ok(())));
+			assert_eq!(Balances::free_balance(Charlie.to_account_id()), before + UNITS);
+		});
+	}
+
+	#[test]
+	fn transactions_are_signed_by_index() {
+		new_test_ext().execute_with(|| {
+			initialize_block(1);
+			claim_index(7);
+
+			let remark = Call::System(frame_system::Call::remark(vec![]));
+			let uxt = sign_as(Charlie, MultiAddress::Index(7), remark, 1, 0);
+			assert_eq!(Executive::apply_extrinsic(uxt), Ok(Ok(())));
+			assert_eq!(System::account_nonce(Charlie.to_account_id()), 2);
+
+			// An unclaimed index can't sign.
+			let remark = Call::System(frame_system::Call::remark(vec![]));
+			let uxt = sign_as(Charlie, MultiAddress::Index(8), remark, 2, 0);
+			assert_eq!(
+				Executive::apply_extrinsic(uxt),
+				Err(UnknownTransaction::CannotLookup.into()),
+			);
+		});
+	}
+
+	fn raw(name: &str) -> Data {
+		Data::Raw(name.as_bytes().to_vec())
+	}
+
+	/// An identity with only a display name.
+	fn identity(display: &str) -> IdentityInfo {
+		IdentityInfo {
+			additional: vec![],
+			display: raw(display),
+			legal: Data::None,
+			web: Data::None,
+			riot: Data::None,
+			email: Data::None,
+			pgp_fingerprint: None,
+			image: Data::None,
+			twitter: Data::None,
+		}
+	}
+
+	#[test]
+	fn display_names_resolve_identities_and_sub_accounts() {
+		new_test_ext().execute_with(|| {
+			let bob = Origin::signed(Bob.to_account_id());
+			assert_ok!(Identity::set_identity(bob.clone(), identity("Bob")));
+			assert_ok!(Identity::set_subs(bob, vec![(Charlie.to_account_id(), raw("stash"))]));
+
+			assert_eq!(apis::display_name(&Bob.to_account_id()), Some(b"Bob".to_vec()));
+			assert_eq!(apis::display_name(&Charlie.to_account_id()), Some(b"Bob/stash".to_vec()));
+			assert_eq!(apis::display_name(&Alice.to_account_id()), None);
+
+			// Sub-accounts without a raw name resolve to their parent.
+			let bob = Origin::signed(Bob.to_account_id());
+			assert_ok!(Identity::set_subs(bob, vec![(Charlie.to_account_id(), Data::None)]));
+			assert_eq!(apis::display_name(&Charlie.to_account_id()), Some(b"Bob".to_vec()));
+		});
+	}
+}
@@ -0,0 +1,81 @@
+//! Test utilities to execute extrinsics against the runtime.
+
+use codec::Encode;
+use frame_support::traits::GenesisBuild;
+use sp_consensus_aura::AURA_ENGINE_ID;
+use sp_keyring::Sr25519Keyring;
+use sp_runtime::{generic::{Digest, Era, SignedPayload}, traits::Header as _};
+use crate::*;
+
+/// Free balance of every endowed account at genesis.
+pub const INITIAL_BALANCE: Balance = 1 << 60;
+
+/// Build genesis storage with `Alice` as the only Aura authority and `Alice`, `Bob` and
+/// `Charlie` endowed with `INITIAL_BALANCE`.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut t = frame_system::GenesisConfig::default().build_storage::<Runtime>().unwrap();
+
+	pallet_balances::GenesisConfig::<Runtime> {
+		balances: vec![
+			(Sr25519Keyring::Alice.to_account_id(), INITIAL_BALANCE),
+			(Sr25519Keyring::Bob.to_account_id(), INITIAL_BALANCE),
+			(Sr25519Keyring::Charlie.to_account_id(), INITIAL_BALANCE),
+		],
+	}.assimilate_storage(&mut t).unwrap();
+
+	pallet_aura::GenesisConfig::<Runtime> {
+		authorities: vec![Sr25519Keyring::Alice.public().into()],
+	}.assimilate_storage(&mut t).unwrap();
+
+	t.into()
+}
+
+/// Initialize block `number` as authored by `Alice`, the only authority.
+pub fn initialize_block(number: BlockNumber) {
+	// Aura slots must increase, so derive the slot from the block number.
+	let slot = number as u64;
+	let digest = Digest { logs: vec![DigestItem::PreRuntime(AURA_ENGINE_ID, slot.encode())] };
+	let header = Header::new(
+		number,
+		Default::default(),
+		Default::default(),
+		System::parent_hash(),
+		digest,
+	);
+
+	Executive::initialize_block(&header);
+}
+
+/// The `SignedExtra` of an immortal transaction.
+pub fn signed_extra(nonce: Index, tip: Balance) -> SignedExtra {
+	(
+		frame_system::CheckSpecVersion::new(),
+		frame_system::CheckTxVersion::new(),
+		frame_system::CheckGenesis::new(),
+		frame_system::CheckEra::from(Era::Immortal),
+		frame_system::CheckNonce::from(nonce),
+		frame_system::CheckWeight::new(),
+		extensions::ChargeTransactionPaymentByTip::from(tip),
+		pallet_template::CheckFreeCall::new(),
+	)
+}
+
+/// Sign `call` on behalf of `signer`.
+pub fn sign(signer: Sr25519Keyring, call: Call, nonce: Index, tip: Balance) -> UncheckedExtrinsic {
+	sign_as(signer, signer.to_account_id().into(), call, nonce, tip)
+}
+
+/// Sign `call` on behalf of `signer`, designated by `address`.
+pub fn sign_as(
+	signer: Sr25519Keyring,
+	address: Address,
+	call: Call,
+	nonce: Index,
+	tip: Balance,
+) -> UncheckedExtrinsic {
+	let payload = SignedPayload::new(call, signed_extra(nonce, tip)).unwrap();
+	let signature = payload.using_encoded(|payload| signer.sign(payload));
+	let (call, extra, _) = payload.deconstruct();
+
+	UncheckedExtrinsic::new_signed(call, address, signature.into(), extra)
+}
@@ -0,0 +1,30 @@
+#!/usr/bin/env bash
+# This script is meant to be run on Unix/Linux based systems, on the reference hardware the
+# weights are generated for.
+#
+# Usage: ./scripts/benchmark.sh <pallet>, where <pallet> is a directory of `pallets/`, e.g. `nft`.
+set -e
+
+PALLET=$1
+if [ -z "$PALLET" ] ; then
+	echo "Usage: $0 <pallet>" >&2
+	exit 1
+fi
+
+cd "$(dirname "$0")/.."
+
+echo "*** Building the node with the benchmarks"
+cargo build --release --manifest-path node/Cargo.toml --features runtime-benchmarks
+
+echo "*** Generating pallets/$PALLET/src/weights.rs"
+./target/release/node-template benchmark \
+	--chain dev \
+	--execution wasm \
+	--wasm-execution compiled \
+	--pallet "pallet_${PALLET//-/_}" \
+	--extrinsic '*' \
+	--steps 50 \
+	--repeat 20 \
+	--heap-pages 4096 \
+	--template ./.maintain/frame-weight-template.hbs \
+	--output "./pallets/$PALLET/src/weights.rs"